@@ -0,0 +1,342 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::{
+    constants::{K_ACHIEVEMENTS_PATH, K_PROFILE_PATH, K_TOAST_SECS},
+    data::DataPlugin,
+    events::{Catch, Hit, Landed, Sold},
+    items::FishType,
+    resources::Calendar,
+    states::GameState,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Achievement System
+///
+/// Gameplay events advance the goals of the achievements table, unlocks are shown as toasts and
+/// kept in a profile file across runs. The profile is written on unlocks, and on state changes
+/// and exit when there is progress to keep, rather than on every bit of progress.
+
+/// What an achievement counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Goal {
+    // Strikes, landed or not
+    Strike,
+    // Landed fish, of any species with `None`, at night only with `night`
+    Land { t: Option<FishType>, night: bool },
+    // Fish sold at the hut
+    Sell,
+    Hit,
+    Enter(GameState),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AchievementDef {
+    // Stable key in the profile file
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal: Goal,
+    pub count: u32,
+}
+
+/// Achievements to unlock, the list of the achievements data file.
+#[derive(Resource, Asset, TypePath, Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct AchievementTable {
+    pub achievements: Vec<AchievementDef>,
+}
+
+/// Achievement progress across runs, by achievement id.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub progress: HashMap<String, u32>,
+    pub unlocked: HashSet<String>,
+    // Progress not written to the file yet
+    #[serde(skip)]
+    pub dirty: bool,
+}
+
+impl Profile {
+    /// The profile file, or a new profile without one.
+    pub fn read() -> Self {
+        let profile = fs::read_to_string(K_PROFILE_PATH)
+            .map_err(|error| error.to_string())
+            .and_then(|text| ron::from_str(&text).map_err(|error| error.to_string()));
+        match profile {
+            Ok(profile) => profile,
+            Err(error) => {
+                info!("New profile: {}", error);
+                Profile::default()
+            }
+        }
+    }
+
+    pub fn write(&self) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())?;
+        fs::write(K_PROFILE_PATH, text).map_err(|error| error.to_string())
+    }
+
+    /// Advances the locked achievements of the table whose goal matches, returns the ones
+    /// unlocked.
+    pub fn advance<'a>(
+        &mut self,
+        table: &'a AchievementTable,
+        matches: impl Fn(&Goal) -> bool,
+        amount: u32,
+    ) -> Vec<&'a AchievementDef> {
+        let mut unlocked = Vec::new();
+        for def in &table.achievements {
+            if self.unlocked.contains(&def.id) || !matches(&def.goal) {
+                continue;
+            }
+            let progress = self.progress.entry(def.id.clone()).or_default();
+            *progress += amount;
+            self.dirty = true;
+            if *progress >= def.count {
+                self.unlocked.insert(def.id.clone());
+                unlocked.push(def);
+            }
+        }
+        unlocked
+    }
+
+    /// Writes the profile if it has progress the file doesn't.
+    fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        match self.write() {
+            Ok(()) => self.dirty = false,
+            Err(error) => warn!("Profile not saved: {}", error),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub timer: Timer,
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(DataPlugin::<AchievementTable>::new(K_ACHIEVEMENTS_PATH))
+            .insert_resource(Profile::read())
+            .add_observer(on_catch)
+            .add_observer(on_landed)
+            .add_observer(on_sold)
+            .add_observer(on_hit)
+            // Toasts and the profile are handled in every state.
+            .add_systems(Update, (state_transitions, toasts).chain())
+            .add_systems(Last, save_profile);
+    }
+}
+
+/// Shows the unlocked achievements and saves the profile when there are any.
+fn unlock(commands: &mut Commands, profile: &mut Profile, unlocked: Vec<&AchievementDef>) {
+    if !unlocked.is_empty() {
+        profile.save();
+    }
+    for def in unlocked {
+        info!("Achievement unlocked: {}", def.name);
+        commands.spawn((
+            Text::new(format!(
+                "Achievement unlocked: {}\n{}",
+                def.name, def.description
+            )),
+            TextFont::from_font_size(18.0),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(8),
+                right: px(8),
+                padding: UiRect::all(px(8)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            Toast {
+                timer: Timer::from_seconds(K_TOAST_SECS, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+pub fn on_catch(
+    _catch: On<Catch>,
+    table: Res<AchievementTable>,
+    mut profile: ResMut<Profile>,
+    mut commands: Commands,
+) {
+    let unlocked = profile.advance(&table, |goal| *goal == Goal::Strike, 1);
+    unlock(&mut commands, &mut profile, unlocked);
+}
+
+pub fn on_landed(
+    landed: On<Landed>,
+    calendar: Res<Calendar>,
+    table: Res<AchievementTable>,
+    mut profile: ResMut<Profile>,
+    mut commands: Commands,
+) {
+    let t = landed.fish.t;
+    let night = calendar.is_night();
+    let unlocked = profile.advance(
+        &table,
+        |goal| match goal {
+            Goal::Land {
+                t: species,
+                night: at_night,
+            } => species.is_none_or(|species| species == t) && (night || !at_night),
+            _ => false,
+        },
+        1,
+    );
+    unlock(&mut commands, &mut profile, unlocked);
+}
+
+pub fn on_sold(
+    sold: On<Sold>,
+    table: Res<AchievementTable>,
+    mut profile: ResMut<Profile>,
+    mut commands: Commands,
+) {
+    let unlocked = profile.advance(&table, |goal| *goal == Goal::Sell, sold.fish.len() as u32);
+    unlock(&mut commands, &mut profile, unlocked);
+}
+
+pub fn on_hit(
+    _hit: On<Hit>,
+    table: Res<AchievementTable>,
+    mut profile: ResMut<Profile>,
+    mut commands: Commands,
+) {
+    let unlocked = profile.advance(&table, |goal| *goal == Goal::Hit, 1);
+    unlock(&mut commands, &mut profile, unlocked);
+}
+
+/// Advances the goals of the states entered and saves the progress made in the one left.
+pub fn state_transitions(
+    mut transitions: MessageReader<StateTransitionEvent<GameState>>,
+    table: Res<AchievementTable>,
+    mut profile: ResMut<Profile>,
+    mut commands: Commands,
+) {
+    for transition in transitions.read() {
+        let Some(entered) = transition.entered else {
+            continue;
+        };
+        if transition.exited == Some(entered) {
+            continue;
+        }
+        let unlocked = profile.advance(&table, |goal| *goal == Goal::Enter(entered), 1);
+        unlock(&mut commands, &mut profile, unlocked);
+        profile.save();
+    }
+}
+
+/// Saves the profile when the game exits.
+pub fn save_profile(mut exits: MessageReader<AppExit>, mut profile: ResMut<Profile>) {
+    if exits.read().count() > 0 {
+        profile.save();
+    }
+}
+
+/// Stacks the toasts, newest at the bottom, and removes them on real time so they also expire
+/// while the game is paused.
+pub fn toasts(
+    time: Res<Time<Real>>,
+    query: Query<(Entity, &mut Toast, &mut Node)>,
+    mut commands: Commands,
+) {
+    let mut shown = Vec::new();
+    for (entity, mut toast, node) in query {
+        toast.timer.tick(time.delta());
+        if toast.timer.is_finished() {
+            commands.entity(entity).despawn();
+        } else {
+            shown.push((toast.timer.elapsed(), node));
+        }
+    }
+    // The oldest toasts have been shown the longest.
+    shown.sort_by_key(|(elapsed, _)| Reverse(*elapsed));
+    for (index, (_, mut node)) in shown.into_iter().enumerate() {
+        node.top = px(8.0 + 56.0 * index as f32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(id: &str, goal: Goal, count: u32) -> AchievementDef {
+        AchievementDef {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            goal,
+            count,
+        }
+    }
+
+    #[test]
+    fn achievements_unlock_once_at_their_count() {
+        let table = AchievementTable {
+            achievements: vec![
+                def("strike_1", Goal::Strike, 1),
+                def("strike_3", Goal::Strike, 3),
+                def("sell_2", Goal::Sell, 2),
+            ],
+        };
+        let mut profile = Profile::default();
+        let strike = |goal: &Goal| *goal == Goal::Strike;
+
+        let unlocked = profile.advance(&table, strike, 1);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].id, "strike_1");
+        assert!(profile.advance(&table, strike, 1).is_empty());
+        let unlocked = profile.advance(&table, strike, 1);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].id, "strike_3");
+        assert!(profile.advance(&table, strike, 1).is_empty());
+
+        // Unlocked achievements stop counting, others are untouched.
+        assert_eq!(profile.progress["strike_1"], 1);
+        assert_eq!(profile.progress["strike_3"], 3);
+        assert!(!profile.progress.contains_key("sell_2"));
+
+        let unlocked = profile.advance(&table, |goal| *goal == Goal::Sell, 5);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(profile.unlocked.len(), 3);
+    }
+
+    #[test]
+    fn only_progress_needs_saving() {
+        let table = AchievementTable {
+            achievements: vec![def("strike_3", Goal::Strike, 3)],
+        };
+        let mut profile = Profile::default();
+        profile.advance(&table, |goal| *goal == Goal::Sell, 1);
+        assert!(!profile.dirty);
+        profile.advance(&table, |goal| *goal == Goal::Strike, 1);
+        assert!(profile.dirty);
+
+        // The flag is not part of the file.
+        let text = ron::to_string(&profile).unwrap();
+        let loaded: Profile = ron::from_str(&text).unwrap();
+        assert!(!loaded.dirty);
+        assert_eq!(loaded.progress, profile.progress);
+    }
+
+    #[test]
+    fn achievements_data_file_parses() {
+        let table: AchievementTable =
+            ron::from_str(include_str!("../assets/data/achievements.ron")).unwrap();
+        assert!(!table.achievements.is_empty());
+        let ids: HashSet<&String> = table.achievements.iter().map(|def| &def.id).collect();
+        assert_eq!(ids.len(), table.achievements.len());
+    }
+}
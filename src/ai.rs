@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::{
+    components::OnAI,
+    constants::{
+        K_DECISION_JITTER, K_DECISION_SECS, K_PATROL_REACH, K_PATROL_SCORE, K_REST_SECS,
+        K_REST_SPEED, K_SPEED, K_TIRE_SECS, K_WANDER_SCORE,
+    },
+};
+use bevy::prelude::*;
+use rand::Rng;
+
+/// AI System
+///
+/// Utility AI on top of `OnAI` entities: sensing systems fill `Senses`, every `Brain` scores
+/// its behaviours and the best one sets the `Steering` the movement of the entity follows.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    Wander,
+    Flee,
+    Seek,
+    Patrol,
+    Rest,
+}
+
+/// Something sensed by an AI entity, `score` in [0, 1] is how much it matters and `speed` is a
+/// multiplier of `K_SPEED` to react with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stimulus {
+    pub position: Vec2,
+    pub score: f32,
+    pub speed: f32,
+}
+
+/// Strongest threat and target sensed this frame.
+#[derive(Default, Component, Debug, Clone, PartialEq)]
+pub struct Senses {
+    pub threat: Option<Stimulus>,
+    pub target: Option<Stimulus>,
+}
+
+impl Senses {
+    pub fn threat(&mut self, stimulus: Stimulus) {
+        if self
+            .threat
+            .is_none_or(|threat| threat.score < stimulus.score)
+        {
+            self.threat = Some(stimulus);
+        }
+    }
+
+    pub fn target(&mut self, stimulus: Stimulus) {
+        if self
+            .target
+            .is_none_or(|target| target.score < stimulus.score)
+        {
+            self.target = Some(stimulus);
+        }
+    }
+}
+
+/// Velocity asked for by the active behaviour, in world units per second. Each kind of entity
+/// moves towards it within its own limits.
+#[derive(Default, Component, Debug, Clone, PartialEq)]
+pub struct Steering {
+    pub desired: Vec2,
+}
+
+/// Per-entity cadence of slow decisions such as picking a new wander direction, jittered so
+/// entities don't decide on the same frame.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct DecisionTimer {
+    pub timer: Timer,
+}
+
+impl DecisionTimer {
+    fn jittered() -> Duration {
+        let jitter = rand::thread_rng().gen_range(-K_DECISION_JITTER..K_DECISION_JITTER);
+        Duration::from_secs_f32(K_DECISION_SECS + jitter)
+    }
+}
+
+impl Default for DecisionTimer {
+    fn default() -> Self {
+        DecisionTimer {
+            timer: Timer::new(DecisionTimer::jittered(), TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(Senses, DecisionTimer, Steering)]
+pub struct Brain {
+    pub behaviours: Vec<Behaviour>,
+    pub active: Behaviour,
+    // Last scores, in the order of `behaviours`
+    pub scores: Vec<f32>,
+    // Tiredness in [0, 1], grows with fast moves and drops while resting
+    pub fatigue: f32,
+}
+
+impl Brain {
+    pub fn new(behaviours: Vec<Behaviour>) -> Self {
+        let scores = vec![0.0; behaviours.len()];
+        Brain {
+            behaviours,
+            active: Behaviour::Wander,
+            scores,
+            fatigue: 0.0,
+        }
+    }
+}
+
+/// Waypoints visited in a loop by the `Patrol` behaviour.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Patrol {
+    pub points: Vec<Vec2>,
+    pub index: usize,
+}
+
+/// Shows the active behaviour and scores above every brain.
+#[derive(Resource, Default, Debug)]
+pub struct AiDebug {
+    pub enabled: bool,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct BrainLabel {
+    pub entity: Entity,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AiSet {
+    Tick,
+    Sense,
+    Think,
+    Act,
+}
+
+/// Decision and steering stages of the AI, the plugins of the entities add their sensing systems.
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiDebug>()
+            .configure_sets(
+                Update,
+                (AiSet::Tick, AiSet::Sense, AiSet::Think, AiSet::Act).chain(),
+            )
+            .add_systems(Update, ai_decision_timer.in_set(AiSet::Tick))
+            .add_systems(Update, ai_think.in_set(AiSet::Think))
+            .add_systems(Update, ai_act.in_set(AiSet::Act))
+            .add_systems(Update, (ai_debug_input, ai_debug_labels).chain());
+    }
+}
+
+fn score(behaviour: Behaviour, brain: &Brain, senses: &Senses, patrol: bool) -> f32 {
+    match behaviour {
+        Behaviour::Wander => K_WANDER_SCORE,
+        Behaviour::Flee => senses.threat.map_or(0.0, |threat| threat.score),
+        Behaviour::Seek => senses.target.map_or(0.0, |target| target.score),
+        Behaviour::Patrol => {
+            if patrol {
+                K_PATROL_SCORE
+            } else {
+                0.0
+            }
+        }
+        Behaviour::Rest => brain.fatigue * brain.fatigue,
+    }
+}
+
+pub fn ai_decision_timer(time: Res<Time<Virtual>>, query: Query<&mut DecisionTimer>) {
+    for mut decision in query {
+        if decision.timer.is_finished() {
+            let duration = DecisionTimer::jittered();
+            decision.timer.set_duration(duration);
+            decision.timer.reset();
+        }
+        decision.timer.tick(time.delta());
+    }
+}
+
+pub fn ai_think(query: Query<(&mut Brain, &Senses, Has<Patrol>)>) {
+    for (mut brain, senses, patrol) in query {
+        let mut best = (Behaviour::Wander, f32::MIN);
+        for index in 0..brain.behaviours.len() {
+            let behaviour = brain.behaviours[index];
+            let value = score(behaviour, &brain, senses, patrol);
+            brain.scores[index] = value;
+            if value > best.1 {
+                best = (behaviour, value);
+            }
+        }
+        brain.active = best.0;
+    }
+}
+
+pub fn ai_act(
+    time: Res<Time<Virtual>>,
+    query: Query<
+        (
+            &mut Brain,
+            &mut Senses,
+            &mut Steering,
+            &Transform,
+            Option<&mut Patrol>,
+        ),
+        With<OnAI>,
+    >,
+) {
+    let dt = time.delta_secs();
+    for (mut brain, mut senses, mut steering, transform, patrol) in query {
+        let position = transform.translation.truncate();
+        let mut speed = 1.0;
+        match brain.active {
+            Behaviour::Wander => {
+                steering.desired = steering.desired.normalize_or_zero() * K_SPEED;
+            }
+            Behaviour::Flee => {
+                if let Some(threat) = senses.threat {
+                    speed = threat.speed;
+                    steering.desired =
+                        (position - threat.position).normalize_or_zero() * K_SPEED * speed;
+                }
+            }
+            Behaviour::Seek => {
+                if let Some(target) = senses.target {
+                    speed = target.speed;
+                    steering.desired =
+                        (target.position - position).normalize_or_zero() * K_SPEED * speed;
+                }
+            }
+            Behaviour::Patrol => {
+                if let Some(mut patrol) = patrol
+                    && !patrol.points.is_empty()
+                {
+                    let point = patrol.points[patrol.index];
+                    if position.distance(point) < K_PATROL_REACH {
+                        patrol.index = (patrol.index + 1) % patrol.points.len();
+                    }
+                    steering.desired = (point - position).normalize_or_zero() * K_SPEED;
+                }
+            }
+            Behaviour::Rest => {
+                speed = 0.0;
+                steering.desired = steering.desired.normalize_or_zero() * K_SPEED * K_REST_SPEED;
+            }
+        }
+
+        brain.fatigue = if speed > 1.0 {
+            brain.fatigue + dt / K_TIRE_SECS
+        } else if speed == 0.0 {
+            brain.fatigue - dt / K_REST_SECS
+        } else {
+            brain.fatigue - dt / K_TIRE_SECS
+        }
+        .clamp(0.0, 1.0);
+
+        *senses = Senses::default();
+    }
+}
+
+pub fn ai_debug_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut ai_debug: ResMut<AiDebug>) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        ai_debug.enabled = !ai_debug.enabled;
+        info!("AI debug: {}", ai_debug.enabled);
+    }
+}
+
+pub fn ai_debug_labels(
+    debug: Res<AiDebug>,
+    brains: Query<(Entity, &Brain, &GlobalTransform)>,
+    labels: Query<(Entity, &BrainLabel, &mut Text2d, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let mut labelled = HashSet::new();
+    for (entity, label, mut text, mut transform) in labels {
+        match brains.get(label.entity) {
+            Ok((_, brain, brain_transform)) if debug.enabled => {
+                let scores: Vec<String> = brain
+                    .behaviours
+                    .iter()
+                    .zip(brain.scores.iter())
+                    .map(|(behaviour, score)| format!("{:?} {:.2}", behaviour, score))
+                    .collect();
+                text.0 = format!("[{:?}]\n{}", brain.active, scores.join("\n"));
+                transform.translation = brain_transform.translation().with_z(9.0) + Vec3::Y * 48.0;
+                labelled.insert(label.entity);
+            }
+            _ => commands.entity(entity).despawn(),
+        }
+    }
+
+    if !debug.enabled {
+        return;
+    }
+    for (entity, _, transform) in brains {
+        if !labelled.contains(&entity) {
+            commands.spawn((
+                Text2d::new(""),
+                TextFont::from_font_size(10.0),
+                Transform::from_translation(transform.translation().with_z(9.0)),
+                BrainLabel { entity },
+            ));
+        }
+    }
+}
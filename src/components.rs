@@ -1,21 +1,19 @@
-use crate::items::{self, FishType};
+use std::ops::Range;
+
+use crate::{
+    constants::{
+        K_DISCOUNT, K_DISCOUNT_REPUTATION, K_INVENTORY_SIZE, K_LICENSE_DAYS, K_REPUTATION_MAX,
+    },
+    items::{self, BaitType, FishType},
+    layer,
+};
 use bevy::prelude::*;
 
 /// Component Types
 
-#[derive(Component, Debug, Clone, PartialEq)]
-pub struct DefaultColor {
-    pub color: Color,
-}
-
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct DayNightColor;
 
-#[derive(Default, Component, Debug, Clone, PartialEq)]
-pub struct AnimationTimer {
-    pub timer: Timer,
-}
-
 #[derive(Default, Component, Debug, Clone, PartialEq)]
 pub enum AnimationState {
     Run,
@@ -23,29 +21,32 @@ pub enum AnimationState {
     Finish,
 }
 
-#[derive(Component, Debug, Clone, PartialEq)]
-pub struct AnimationConfig {
-    pub first_index: usize,
-    pub last_index: usize,
-    // Duration per frame in ms
-    pub ms: u64,
-    pub mode: TimerMode,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationEvent {
+    Catch,
+    Hit,
 }
 
-#[derive(Component, Debug, Clone)]
-pub struct SpriteCollection {
-    pub sprites: Vec<Sprite>,
-    pub animations: Vec<AnimationConfig>,
+// The layer animations with the events of this game
+pub type FrameEvent = layer::FrameEvent<AnimationEvent>;
+pub type AnimationConfig = layer::AnimationConfig<AnimationEvent>;
+pub type SpriteCollection = layer::SpriteCollection<AnimationEvent>;
+
+#[derive(Default, Component, Debug, Clone, PartialEq)]
+pub struct Velocity {
+    pub value: f32,
 }
 
-#[derive(Component, Debug, Clone)]
-pub struct ActiveSprite {
-    pub index: usize,
+/// Planar swimming in world units per second, `velocity` turns towards the AI `Steering`.
+#[derive(Default, Component, Debug, Clone, PartialEq)]
+pub struct Swim {
+    pub velocity: Vec2,
 }
 
+/// Seconds since a predator last ate.
 #[derive(Default, Component, Debug, Clone, PartialEq)]
-pub struct Velocity {
-    pub value: f32,
+pub struct Hunger {
+    pub time: f32,
 }
 
 #[derive(Component, Debug, Clone, PartialEq, Eq)]
@@ -54,10 +55,89 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Component, Debug, Clone, PartialEq)]
+#[derive(Default, Component, Debug, Clone, PartialEq)]
 pub struct Player {
     pub money: f32,
     pub items: Vec<items::Item>,
+    // Equipped bait type
+    pub bait: Option<BaitType>,
+    // Last in-game day covered by the fishing license
+    pub license: Option<u32>,
+    // Conservation reputation
+    pub reputation: f32,
+}
+
+impl Player {
+    pub fn add_reputation(&mut self, delta: f32) {
+        self.reputation = (self.reputation + delta).clamp(-K_REPUTATION_MAX, K_REPUTATION_MAX);
+    }
+
+    /// Price multiplier at the hut, trusted fishers get a discount.
+    pub fn discount(&self) -> f32 {
+        if self.reputation >= K_DISCOUNT_REPUTATION {
+            1.0 - K_DISCOUNT
+        } else {
+            1.0
+        }
+    }
+
+    pub fn has_license(&self, day: u32) -> bool {
+        self.license.is_some_and(|last| day <= last)
+    }
+
+    /// Extends the license after its last day, or from `day` once it has expired, returns the
+    /// new last day.
+    pub fn extend_license(&mut self, day: u32) -> u32 {
+        let first = match self.license {
+            Some(last) if last >= day => last + 1,
+            _ => day,
+        };
+        let last = first + K_LICENSE_DAYS - 1;
+        self.license = Some(last);
+        last
+    }
+
+    /// Takes the fine out of the money, without going into debt, returns the amount paid.
+    pub fn pay_fine(&mut self, fine: f32) -> f32 {
+        let paid = fine.min(self.money.max(0.0));
+        self.money -= paid;
+        paid
+    }
+
+    /// Adds bait to its stack, or to a new slot if there is room. Nothing is added without bait.
+    pub fn add_bait(&mut self, t: BaitType, count: u32) -> bool {
+        if count == 0 {
+            return false;
+        }
+        for item in self.items.iter_mut() {
+            if let items::Item::Bait(bait) = item
+                && bait.t == t
+            {
+                bait.count += count;
+                return true;
+            }
+        }
+        if self.items.len() < K_INVENTORY_SIZE {
+            self.items.push(items::Item::Bait(items::Bait { t, count }));
+            return true;
+        }
+        false
+    }
+
+    /// Takes one piece of the equipped bait out of the inventory.
+    pub fn take_bait(&mut self) -> Option<BaitType> {
+        let t = self.bait?;
+        let index = self.items.iter().position(
+            |item| matches!(item, items::Item::Bait(bait) if bait.t == t && bait.count > 0),
+        )?;
+        if let items::Item::Bait(bait) = &mut self.items[index] {
+            bait.count -= 1;
+            if bait.count == 0 {
+                self.items.remove(index);
+            }
+        }
+        Some(t)
+    }
 }
 
 #[derive(Component, Debug, Clone, PartialEq)]
@@ -68,6 +148,124 @@ pub struct Fish {
     pub t: FishType,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protection {
+    // Catches are fined and cost reputation
+    Banned,
+    // Catches cost reputation
+    Limited,
+}
+
+/// Marine protected area over the full water depth, marked by buoys at the surface.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ProtectedArea {
+    pub protection: Protection,
+    // Spawn rate multiplier of the spawn zones inside
+    pub recovery: f32,
+    pub width: f32,
+}
+
+impl ProtectedArea {
+    pub fn new(protection: Protection, recovery: f32, width: f32) -> Self {
+        ProtectedArea {
+            protection,
+            recovery,
+            width,
+        }
+    }
+
+    pub fn contains(&self, center: Vec3, position: Vec3) -> bool {
+        (position.x - center.x).abs() <= self.width / 2.0
+    }
+
+    /// Spawn rate multiplier at a position, the best of the areas centered where given that
+    /// contain it.
+    pub fn recovery_at<'a>(
+        areas: impl IntoIterator<Item = (&'a ProtectedArea, Vec3)>,
+        position: Vec3,
+    ) -> f32 {
+        areas
+            .into_iter()
+            .filter(|(area, center)| area.contains(*center, position))
+            .map(|(area, _)| area.recovery)
+            .fold(1.0, f32::max)
+    }
+}
+
+/// Area of the level where fish of the weighted species are spawned.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct SpawnZone {
+    pub weights: Vec<(FishType, f32)>,
+    // Depth below the water surface, spanning the depth bands of the species
+    pub depth: Range<f32>,
+    // Fish spawned per second
+    pub rate: f32,
+    // Fish of the zone species inside the zone
+    pub cap: usize,
+    pub width: f32,
+}
+
+impl SpawnZone {
+    /// Zone over the depth bands of its species.
+    pub fn new(weights: Vec<(FishType, f32)>, rate: f32, cap: usize, width: f32) -> Self {
+        let depth = weights
+            .iter()
+            .map(|(t, _)| t.depth())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+            .unwrap_or(0.0..0.0);
+        SpawnZone {
+            weights,
+            depth,
+            rate,
+            cap,
+            width,
+        }
+    }
+
+    /// Species picked by weight, `roll` in [0, 1).
+    pub fn pick(&self, roll: f32) -> Option<FishType> {
+        let total: f32 = self.weights.iter().map(|(_, weight)| weight).sum();
+        let mut roll = roll * total;
+        for (t, weight) in &self.weights {
+            if roll < *weight {
+                return Some(*t);
+            }
+            roll -= weight;
+        }
+        self.weights.last().map(|(t, _)| *t)
+    }
+
+    pub fn spawns(&self, t: &FishType) -> bool {
+        self.weights
+            .iter()
+            .any(|(other, weight)| other == t && *weight > 0.0)
+    }
+
+    /// Whether a position of the zone's layer lies inside the zone centered at `center`.
+    pub fn contains(&self, center: Vec3, position: Vec3) -> bool {
+        // The zone is centered on the middle of its depth range.
+        let depth = (self.depth.start + self.depth.end) / 2.0 + center.y - position.y;
+        (position.x - center.x).abs() <= self.width / 2.0 && self.depth.contains(&depth)
+    }
+}
+
+/// Fishing hook cast from the boat, `depth` is below the water surface.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct FishingHook {
+    pub depth: f32,
+    // Seconds since the hook last moved
+    pub still: f32,
+    // Bait on the hook
+    pub bait: Option<BaitType>,
+    // Bait spent on the current strike
+    pub strike: Option<BaitType>,
+    // Fish landed on the current strike, until it is released
+    pub landed: Option<items::Fish>,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct FishingLine;
+
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct Land {
     pub size: Vec2,
@@ -92,16 +290,15 @@ pub struct Cloud {
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct Sky;
 
+/// Sprite sized relative to the viewport, `size` is given at the virtual resolution.
 #[derive(Component, Debug, Clone, PartialEq)]
-pub struct ActionRange {
-    pub range: f32,
+pub struct ViewportFit {
+    pub size: Vec2,
 }
 
 #[derive(Component, Debug, Clone, PartialEq)]
-pub struct Layer {
-    pub depth: f32,
-    pub speed: f32,
-    pub size: Vec2,
+pub struct ActionRange {
+    pub range: f32,
 }
 
 #[derive(Component, Debug, Clone, PartialEq)]
@@ -113,15 +310,51 @@ pub struct BoatLayer;
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct CityLayer;
 
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct FaunaLayer;
+
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct SkyLayer;
 
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct PlayerMenu;
 
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct StatsScreen;
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Hud;
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct SummaryScreen;
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct JournalScreen;
+
+/// Character the player talks to, `dialogue` names its dialogue tree.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Npc {
+    pub dialogue: String,
+}
+
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct OnControl;
 
+/// Camera following the player within the world bounds.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct CameraController {
+    // Follow rate, 1/s
+    pub damping: f32,
+    // Offset ahead of the player in the direction of movement
+    pub look_ahead: f32,
+    // Vertical offset when looking at the fish area
+    pub dive: f32,
+    // Projection scale while fishing
+    pub fish_zoom: f32,
+    // Zoom rate, 1/s
+    pub zoom_damping: f32,
+}
+
 #[derive(Component, Debug, Clone, PartialEq)]
 pub struct OnAI;
 
@@ -134,3 +367,145 @@ pub enum PlayerState {
     Hook,
     Attack,
 }
+
+/// Best catch of a species on the hut wall.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct MountedTrophy;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn license_starts_today_without_one() {
+        let mut player = Player::default();
+        assert_eq!(player.extend_license(10), 10 + K_LICENSE_DAYS - 1);
+        assert!(player.has_license(10));
+    }
+
+    #[test]
+    fn license_extends_a_valid_one() {
+        let mut later = Player {
+            license: Some(12),
+            ..default()
+        };
+        assert_eq!(later.extend_license(10), 12 + K_LICENSE_DAYS);
+        let mut today = Player {
+            license: Some(10),
+            ..default()
+        };
+        assert_eq!(today.extend_license(10), 10 + K_LICENSE_DAYS);
+    }
+
+    #[test]
+    fn license_restarts_today_once_expired() {
+        let mut player = Player {
+            license: Some(9),
+            ..default()
+        };
+        assert_eq!(player.extend_license(10), 10 + K_LICENSE_DAYS - 1);
+        assert!(player.has_license(10));
+    }
+
+    #[test]
+    fn fines_stop_at_zero() {
+        let mut player = Player {
+            money: 25.0,
+            ..default()
+        };
+        assert_eq!(player.pay_fine(10.0), 10.0);
+        assert_eq!(player.money, 15.0);
+        assert_eq!(player.pay_fine(20.0), 15.0);
+        assert_eq!(player.money, 0.0);
+        assert_eq!(player.pay_fine(10.0), 0.0);
+        assert_eq!(player.money, 0.0);
+    }
+
+    #[test]
+    fn bait_is_taken_until_the_stack_is_empty() {
+        let mut player = Player::default();
+        player.bait = Some(BaitType::Worm);
+        assert!(!player.add_bait(BaitType::Worm, 0));
+        assert!(player.items.is_empty());
+        assert_eq!(player.take_bait(), None);
+
+        assert!(player.add_bait(BaitType::Worm, 2));
+        assert_eq!(player.take_bait(), Some(BaitType::Worm));
+        assert_eq!(player.take_bait(), Some(BaitType::Worm));
+        assert!(player.items.is_empty());
+        assert_eq!(player.take_bait(), None);
+    }
+
+    #[test]
+    fn empty_bait_stacks_are_skipped() {
+        let mut player = Player::default();
+        player.bait = Some(BaitType::Shrimp);
+        player.items.push(items::Item::Bait(items::Bait {
+            t: BaitType::Shrimp,
+            count: 0,
+        }));
+        assert_eq!(player.take_bait(), None);
+    }
+
+    fn zone() -> SpawnZone {
+        SpawnZone::new(
+            vec![(FishType::Fish, 3.0), (FishType::Ray, 1.0)],
+            1.0,
+            8,
+            100.0,
+        )
+    }
+
+    #[test]
+    fn spawn_zone_spans_species_depths() {
+        let zone = zone();
+        assert_eq!(zone.depth.start, FishType::Fish.depth().start);
+        assert_eq!(zone.depth.end, FishType::Ray.depth().end);
+    }
+
+    #[test]
+    fn spawn_zone_picks_by_weight() {
+        let zone = zone();
+        assert_eq!(zone.pick(0.0), Some(FishType::Fish));
+        assert_eq!(zone.pick(0.74), Some(FishType::Fish));
+        assert_eq!(zone.pick(0.75), Some(FishType::Ray));
+        assert_eq!(zone.pick(0.99), Some(FishType::Ray));
+        assert_eq!(SpawnZone::new(Vec::new(), 1.0, 8, 100.0).pick(0.5), None);
+    }
+
+    #[test]
+    fn recovery_is_the_best_of_the_areas_containing_the_position() {
+        let reserve = ProtectedArea::new(Protection::Banned, 3.0, 100.0);
+        let limited = ProtectedArea::new(Protection::Limited, 1.5, 400.0);
+        let areas = [
+            (&reserve, Vec3::new(0.0, -200.0, 0.0)),
+            (&limited, Vec3::new(0.0, -200.0, 0.0)),
+        ];
+        assert_eq!(ProtectedArea::recovery_at(areas, Vec3::ZERO), 3.0);
+        assert_eq!(
+            ProtectedArea::recovery_at(areas, Vec3::new(51.0, 0.0, 0.0)),
+            1.5
+        );
+        assert_eq!(
+            ProtectedArea::recovery_at(areas, Vec3::new(-201.0, 0.0, 0.0)),
+            1.0
+        );
+        assert_eq!(ProtectedArea::recovery_at([], Vec3::ZERO), 1.0);
+    }
+
+    #[test]
+    fn spawn_zone_contains() {
+        let zone = zone();
+        let middle = (zone.depth.start + zone.depth.end) / 2.0;
+        let center = Vec3::new(1000.0, -100.0, 0.0);
+        assert!(zone.contains(center, center));
+        assert!(zone.contains(center, Vec3::new(1050.0, -100.0, 0.0)));
+        assert!(!zone.contains(center, Vec3::new(1051.0, -100.0, 0.0)));
+        // Above the top of the zone, then at its bottom edge.
+        let top = center.y + middle - zone.depth.start;
+        assert!(!zone.contains(center, Vec3::new(1000.0, top + 1.0, 0.0)));
+        assert!(zone.contains(center, Vec3::new(1000.0, top - 1.0, 0.0)));
+        let bottom = center.y + middle - zone.depth.end;
+        assert!(!zone.contains(center, Vec3::new(1000.0, bottom, 0.0)));
+    }
+}
@@ -1,4 +1,5 @@
 /// Game Constants
+use bevy::math::Vec2;
 
 /// Coordinate space:
 ///
@@ -9,19 +10,118 @@
 ///      +----------------+
 /// (-w/2, -h/2)       (w/2, -h/2)
 ///
-/// TODO: Move to Resources: handle resizes and speed change.
+/// `K_WIDTH` x `K_HEIGHT` is the virtual resolution: the world area the camera always shows,
+/// whatever the window size. See `resources::ViewportConfig`.
 pub const K_WIDTH: f32 = 1280.0;
 pub const K_HEIGHT: f32 = 720.0;
-pub const K_GROUND_LEVEL: f32 = 32.0 - K_HEIGHT / 2.0;
+// Height of the ground above the bottom of the visible area, see `ViewportConfig::ground_level`
+pub const K_GROUND_HEIGHT: f32 = 32.0;
 pub const K_SPEED: f32 = 150.0;
 pub const K_ANIMATION_FRAME_MS: u64 = 100;
 pub const K_SECS_IN_DAY: f32 = 30.0;
 pub const K_OCEAN_LAND_BORDER: f32 = 512.0;
 pub const K_OCEAN_SIZE: f32 = 4096.0;
 pub const K_LAND_SIZE: f32 = 4096.0;
+pub const K_WORLD_LEFT: f32 = K_OCEAN_LAND_BORDER - K_LAND_SIZE;
+pub const K_WORLD_RIGHT: f32 = K_OCEAN_LAND_BORDER + K_OCEAN_SIZE;
 pub const K_SIT_OFFSET: f32 = -22.0;
-pub const K_FISH_MAX_POPULATION: usize = 32;
+pub const K_POPULATION_SECS: f32 = 1.0;
+// In-game days kept in the population history
+pub const K_STATS_DAYS: usize = 30;
+// Population chart size in logical pixels
+pub const K_CHART_SIZE: Vec2 = Vec2::new(480.0, 200.0);
 pub const K_FISH_AREA_BORDER: f32 = K_OCEAN_LAND_BORDER + 512.0;
 pub const K_FISH_AREA_SIZE: f32 = K_OCEAN_SIZE / 2.0;
 pub const K_FISH_CATCH_RANGE: f32 = 64.0;
+// Fish turn rate in rad/s
+pub const K_FISH_TURN_RATE: f32 = 2.0;
+// Maximum fish heading and sprite tilt from horizontal in rad
+pub const K_FISH_MAX_TILT: f32 = 0.35;
+// Schooling neighbourhood, also the fish grid cell size
+pub const K_SCHOOL_RADIUS: f32 = 96.0;
+pub const K_SCHOOL_SEPARATION: f32 = 32.0;
+pub const K_SCHOOL_SEPARATION_WEIGHT: f32 = 1.5;
+pub const K_SCHOOL_ALIGNMENT_WEIGHT: f32 = 1.0;
+pub const K_SCHOOL_COHESION_WEIGHT: f32 = 0.5;
+pub const K_FLEE_RADIUS: f32 = 128.0;
+pub const K_FLEE_SPEED: f32 = 1.3;
+pub const K_HUNT_RADIUS: f32 = 192.0;
+pub const K_HUNT_SPEED: f32 = 1.5;
+pub const K_EAT_RANGE: f32 = 24.0;
+// Fish flee a moving boat closer than this
+pub const K_SPOOK_RADIUS: f32 = 192.0;
+// Fish approach a hook that stayed still this long
+pub const K_HOOK_STILL_SECS: f32 = 2.0;
+pub const K_LURE_RADIUS: f32 = 160.0;
+pub const K_LURE_SPEED: f32 = 0.5;
+pub const K_LURE_SCORE: f32 = 0.6;
+// Utility AI scores and fatigue
+pub const K_DECISION_SECS: f32 = 1.0;
+pub const K_DECISION_JITTER: f32 = 0.4;
+pub const K_WANDER_SCORE: f32 = 0.2;
+pub const K_PATROL_SCORE: f32 = 0.3;
+pub const K_PATROL_REACH: f32 = 32.0;
+pub const K_TIRE_SECS: f32 = 6.0;
+pub const K_REST_SECS: f32 = 3.0;
+pub const K_REST_SPEED: f32 = 0.2;
+// Bite chance without bait, bait multiplies it per species
+pub const K_BITE_CHANCE: f32 = 0.5;
+// Bait pieces bought at once and cut from one fish
+pub const K_BAIT_PACK: u32 = 5;
+pub const K_BAIT_PER_FISH: u32 = 3;
+// Predators hunt once hungry and starve without food
+pub const K_HUNGRY_SECS: f32 = K_SECS_IN_DAY / 4.0;
+pub const K_STARVE_SECS: f32 = 2.0 * K_SECS_IN_DAY;
 pub const K_INVENTORY_SIZE: usize = 15;
+// Rod tip relative to the player facing right
+pub const K_ROD_OFFSET: Vec2 = Vec2::new(48.0, 8.0);
+pub const K_HOOK_CAST_DEPTH: f32 = 16.0;
+pub const K_HOOK_MAX_DEPTH: f32 = 256.0;
+pub const K_HOOK_SPEED: f32 = 96.0;
+// Shorter presses of W/S strike, longer ones move the hook
+pub const K_HOOK_TAP_SECS: f32 = 0.2;
+// Regulations
+pub const K_DAYS_IN_SEASON: u32 = 7;
+pub const K_LICENSE_PRICE: f32 = 5.0;
+pub const K_LICENSE_DAYS: u32 = 7;
+// Inspections per second while the player is at sea
+pub const K_INSPECTION_RATE: f32 = 0.02;
+// Catch and release
+pub const K_RELEASE_REPUTATION: f32 = 1.0;
+// Chance that a released fish of legal size dies
+pub const K_RELEASE_MORTALITY: f32 = 0.3;
+// Marine protected areas
+pub const K_BUOY_SIZE: Vec2 = Vec2::new(8.0, 12.0);
+pub const K_PROTECTED_REPUTATION: f32 = 2.0;
+// Reputation and ecosystem health
+pub const K_REPUTATION_MAX: f32 = 100.0;
+pub const K_SUSTAINABLE_REPUTATION: f32 = 0.2;
+pub const K_OVERFISHING_REPUTATION: f32 = 1.0;
+// Species below this share of their cap are overfished
+pub const K_OVERFISHED_RATIO: f32 = 0.25;
+pub const K_DISCOUNT_REPUTATION: f32 = 10.0;
+pub const K_DISCOUNT: f32 = 0.2;
+pub const K_OPEN_SEA_REPUTATION: f32 = 5.0;
+pub const K_OPEN_SEA_BORDER: f32 = K_FISH_AREA_BORDER + 5.0 * K_FISH_AREA_SIZE / 8.0;
+pub const K_DECLINE_HEALTH: f32 = 0.3;
+pub const K_THRIVING_HEALTH: f32 = 0.8;
+// Mounted trophies relative to the hut, and their spacing
+pub const K_TROPHY_WALL: Vec2 = Vec2::new(-96.0, 24.0);
+pub const K_TROPHY_SPACING: f32 = 96.0;
+// Save file in the working directory
+pub const K_SAVE_PATH: &str = "save.ron";
+// Achievements profile, kept across runs next to the save
+pub const K_PROFILE_PATH: &str = "profile.ron";
+// Achievements table, in the assets
+pub const K_ACHIEVEMENTS_PATH: &str = "data/achievements.ron";
+pub const K_TOAST_SECS: f32 = 4.0;
+// Contracts offered at the hut, in the assets, and how many can be taken at once
+pub const K_CONTRACTS_PATH: &str = "data/contracts.ron";
+pub const K_MAX_CONTRACTS: usize = 3;
+// Dialogue trees in the assets, one file per tree, and the typewriter speed in characters per
+// second
+pub const K_DIALOGUE_DIR: &str = "data/dialogue";
+pub const K_TYPEWRITER_CPS: f32 = 40.0;
+// Night hours, for achievements
+pub const K_NIGHT_START: f32 = 20.0;
+pub const K_NIGHT_END: f32 = 6.0;
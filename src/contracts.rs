@@ -0,0 +1,411 @@
+use crate::{
+    components::{ActionRange, Building, Player},
+    constants::{K_CONTRACTS_PATH, K_MAX_CONTRACTS},
+    data::DataPlugin,
+    events::{Landed, Released, Sold},
+    items::{FishType, Value},
+    plugins::GameSet,
+    resources::{Calendar, Progress},
+    states::GameState,
+    systems::near_building,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Contract System
+///
+/// The hut offers contracts from a data file, accepted ones are tracked until their task is done
+/// through sales, catches and releases, or their deadline passes.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Task {
+    // Sell fish of the species at the hut
+    Deliver {
+        t: FishType,
+        count: u32,
+    },
+    // Land one fish of the species at least this heavy
+    Catch {
+        t: FishType,
+        weight: f32,
+    },
+    // Let fish go, of any species without `t`, juveniles only with `juvenile`
+    Release {
+        #[serde(default)]
+        t: Option<FishType>,
+        count: u32,
+        juvenile: bool,
+    },
+}
+
+impl Task {
+    /// Progress needed to complete the task.
+    pub fn count(&self) -> u32 {
+        match self {
+            Task::Deliver { count, .. } => *count,
+            Task::Catch { .. } => 1,
+            Task::Release { count, .. } => *count,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        match self {
+            Task::Deliver { t, count } => format!("Deliver {} {}", count, t.name()),
+            Task::Catch { t, weight } => format!("Catch a {:.0} kg {}", weight, t.name()),
+            Task::Release { t, count, juvenile } => format!(
+                "Release {} {}{}",
+                count,
+                if *juvenile { "juvenile " } else { "" },
+                t.map_or(String::from("fish"), |t| t.name())
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractDef {
+    pub name: String,
+    pub task: Task,
+    pub reward: f32,
+    // Days to complete the task once accepted
+    pub days: u32,
+}
+
+/// Contracts offered at the hut, the list of the contracts data file.
+#[derive(Resource, Asset, TypePath, Default, Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct ContractBoard {
+    pub offers: Vec<ContractDef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contract {
+    pub def: ContractDef,
+    pub progress: u32,
+    // Last in-game day to complete the task
+    pub deadline: u32,
+}
+
+/// Accepted contracts.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contracts {
+    pub active: Vec<Contract>,
+}
+
+impl Contracts {
+    /// Whether the contract with the name is taken, names identify contracts across changes of
+    /// the data file.
+    pub fn taken(&self, name: &str) -> bool {
+        self.active.iter().any(|contract| contract.def.name == name)
+    }
+
+    /// Takes the contract on the day unless it is already taken or too many are, returns
+    /// whether it was taken.
+    pub fn take(&mut self, def: &ContractDef, day: u32) -> bool {
+        if self.taken(&def.name) {
+            info!("Contract already taken: {}", def.name);
+            return false;
+        }
+        if self.active.len() >= K_MAX_CONTRACTS {
+            info!("Too many contracts");
+            return false;
+        }
+        info!("Contract taken: {}", def.name);
+        self.active.push(Contract {
+            def: def.clone(),
+            progress: 0,
+            // A contract without days is due on the day it is taken.
+            deadline: day + def.days.saturating_sub(1),
+        });
+        true
+    }
+
+    /// Advances the contracts whose task matches, returns the completed ones.
+    pub fn advance(&mut self, matches: impl Fn(&Task) -> u32) -> Vec<Contract> {
+        for contract in self.active.iter_mut() {
+            contract.progress += matches(&contract.def.task);
+        }
+        let (completed, active) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition(|contract| contract.progress >= contract.def.task.count());
+        self.active = active;
+        completed
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ContractHud;
+
+pub struct ContractsPlugin;
+
+impl Plugin for ContractsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(DataPlugin::<ContractBoard>::new(K_CONTRACTS_PATH))
+            .init_resource::<Contracts>()
+            .add_observer(on_sold)
+            .add_observer(on_landed)
+            .add_observer(on_released)
+            .add_systems(Update, contract_deadlines.in_set(GameSet::Reaction))
+            .add_systems(
+                Update,
+                contract_input.run_if(in_state(GameState::InPlayerMenu)),
+            )
+            .add_systems(Startup, spawn_contract_hud)
+            .add_systems(Update, update_contract_hud);
+    }
+}
+
+fn complete(completed: Vec<Contract>, player: &mut Player, progress: &mut Progress) {
+    for contract in completed {
+        info!(
+            "Contract done: {}, reward {:.2}",
+            contract.def.name, contract.def.reward
+        );
+        player.money += contract.def.reward;
+        progress.message = Some(format!(
+            "Contract done: {}! Reward: {:.2}",
+            contract.def.name, contract.def.reward
+        ));
+    }
+}
+
+pub fn on_sold(
+    sold: On<Sold>,
+    mut contracts: ResMut<Contracts>,
+    mut progress: ResMut<Progress>,
+    player: Single<&mut Player>,
+) {
+    let completed = contracts.advance(|task| match task {
+        Task::Deliver { t, .. } => sold.fish.iter().filter(|fish| fish.t == *t).count() as u32,
+        _ => 0,
+    });
+    complete(completed, &mut player.into_inner(), &mut progress);
+}
+
+pub fn on_landed(
+    landed: On<Landed>,
+    mut contracts: ResMut<Contracts>,
+    mut progress: ResMut<Progress>,
+    player: Single<&mut Player>,
+) {
+    let fish = &landed.fish;
+    let completed = contracts.advance(|task| match task {
+        Task::Catch { t, weight } if fish.t == *t && fish.weight >= *weight => 1,
+        _ => 0,
+    });
+    complete(completed, &mut player.into_inner(), &mut progress);
+}
+
+pub fn on_released(
+    released: On<Released>,
+    mut contracts: ResMut<Contracts>,
+    mut progress: ResMut<Progress>,
+    player: Single<&mut Player>,
+) {
+    let completed = contracts.advance(|task| match task {
+        Task::Release { t, juvenile, .. }
+            if t.is_none_or(|t| t == released.fish.t) && (released.juvenile || !juvenile) =>
+        {
+            1
+        }
+        _ => 0,
+    });
+    complete(completed, &mut player.into_inner(), &mut progress);
+}
+
+pub fn contract_deadlines(
+    calendar: Res<Calendar>,
+    mut contracts: ResMut<Contracts>,
+    mut progress: ResMut<Progress>,
+) {
+    let day = calendar.day();
+    if contracts
+        .active
+        .iter()
+        .all(|contract| contract.deadline >= day)
+    {
+        return;
+    }
+    for contract in contracts.active.iter().filter(|c| c.deadline < day) {
+        info!("Contract failed: {}", contract.def.name);
+        progress.message = Some(format!("Contract failed: {}", contract.def.name));
+    }
+    contracts.active.retain(|contract| contract.deadline >= day);
+}
+
+/// Accepts the offer with the pressed number when at the hut.
+pub fn contract_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    calendar: Res<Calendar>,
+    board: Res<ContractBoard>,
+    mut contracts: ResMut<Contracts>,
+    player: Single<&GlobalTransform, With<Player>>,
+    buildings: Query<(&GlobalTransform, &ActionRange), With<Building>>,
+) {
+    const KEYS: [KeyCode; 9] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    let Some(index) = KEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+    else {
+        return;
+    };
+    let Some(def) = board.offers.get(index) else {
+        return;
+    };
+
+    if !near_building(&player, &buildings) {
+        info!("Contracts are taken at the hut");
+    } else {
+        contracts.take(def, calendar.day());
+    }
+}
+
+/// Offers of the board for the player menu.
+pub fn board_lines(board: &ContractBoard, contracts: &Contracts) -> Vec<String> {
+    board
+        .offers
+        .iter()
+        .enumerate()
+        .take(9)
+        .map(|(index, def)| {
+            let taken = if contracts.taken(&def.name) {
+                " (taken)"
+            } else {
+                ""
+            };
+            format!(
+                "[{}] {}: {} in {} days, reward {:.2}{}",
+                index + 1,
+                def.name,
+                def.task.text(),
+                def.days,
+                def.reward,
+                taken
+            )
+        })
+        .collect()
+}
+
+pub fn spawn_contract_hud(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(16.0),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(8),
+            left: px(8),
+            ..default()
+        },
+        ContractHud,
+    ));
+}
+
+pub fn update_contract_hud(contracts: Res<Contracts>, hud: Single<&mut Text, With<ContractHud>>) {
+    if !contracts.is_changed() {
+        return;
+    }
+    let lines: Vec<String> = contracts
+        .active
+        .iter()
+        .map(|contract| {
+            format!(
+                "{}: {} ({}/{}), until day {}",
+                contract.def.name,
+                contract.def.task.text(),
+                contract.progress,
+                contract.def.task.count(),
+                contract.deadline
+            )
+        })
+        .collect();
+    hud.into_inner().0 = lines.join("\n");
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Delivery of rays, shared with the dialogue tests.
+    pub fn def(name: &str, count: u32, days: u32) -> ContractDef {
+        ContractDef {
+            name: name.to_string(),
+            task: Task::Deliver {
+                t: FishType::Ray,
+                count,
+            },
+            reward: 50.0,
+            days,
+        }
+    }
+
+    #[test]
+    fn contracts_are_due_after_their_days() {
+        let mut contracts = Contracts::default();
+        assert!(contracts.take(&def("Week", 1, 7), 10));
+        assert!(contracts.take(&def("Today", 1, 0), 10));
+        assert!(contracts.take(&def("One day", 1, 1), 10));
+        let deadlines: Vec<u32> = contracts.active.iter().map(|c| c.deadline).collect();
+        assert_eq!(deadlines, [16, 10, 10]);
+    }
+
+    #[test]
+    fn contracts_are_taken_once_and_up_to_the_max() {
+        let mut contracts = Contracts::default();
+        assert!(contracts.take(&def("First", 1, 3), 1));
+        assert!(!contracts.take(&def("First", 1, 3), 1));
+        for index in 1..K_MAX_CONTRACTS {
+            assert!(contracts.take(&def(&format!("Other {}", index), 1, 3), 1));
+        }
+        assert!(!contracts.take(&def("One more", 1, 3), 1));
+        assert_eq!(contracts.active.len(), K_MAX_CONTRACTS);
+    }
+
+    #[test]
+    fn changed_offers_are_still_taken_by_name() {
+        let mut contracts = Contracts::default();
+        assert!(contracts.take(&def("Rays", 3, 3), 1));
+        let mut changed = def("Rays", 3, 5);
+        changed.reward = 80.0;
+        assert!(contracts.taken("Rays"));
+        assert!(!contracts.take(&changed, 2));
+        assert_eq!(contracts.active.len(), 1);
+    }
+
+    #[test]
+    fn completed_contracts_are_returned_and_dropped() {
+        let mut contracts = Contracts::default();
+        contracts.take(&def("Small", 2, 3), 1);
+        contracts.take(&def("Big", 5, 3), 1);
+        let rays = |task: &Task| match task {
+            Task::Deliver {
+                t: FishType::Ray, ..
+            } => 1,
+            _ => 0,
+        };
+        assert!(contracts.advance(rays).is_empty());
+        let completed = contracts.advance(rays);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].def.name, "Small");
+        assert_eq!(contracts.active.len(), 1);
+        assert_eq!(contracts.active[0].progress, 2);
+        assert!(contracts.advance(|_| 0).is_empty());
+        assert_eq!(contracts.active[0].progress, 2);
+    }
+
+    #[test]
+    fn contracts_data_file_parses() {
+        let board: ContractBoard =
+            ron::from_str(include_str!("../assets/data/contracts.ron")).unwrap();
+        assert!(!board.offers.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+/// Data Files
+///
+/// Game data written as RON under the assets directory, loaded through the asset server like the
+/// sprites so the files are found wherever the game is started from.
+
+/// Loads an asset of type `A` from a RON file.
+pub struct RonLoader<A>(PhantomData<A>);
+
+impl<A> Default for RonLoader<A> {
+    fn default() -> Self {
+        RonLoader(PhantomData)
+    }
+}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for RonLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<A, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Handle keeping the data file of the `A` resource loaded.
+#[derive(Resource)]
+struct DataFile<A: Asset>(Handle<A>);
+
+/// Resource `A` loaded from the data file at `path` in the assets, empty until it is loaded and
+/// updated when the file changes.
+pub struct DataPlugin<A> {
+    path: &'static str,
+    marker: PhantomData<A>,
+}
+
+impl<A> DataPlugin<A> {
+    pub fn new(path: &'static str) -> Self {
+        DataPlugin {
+            path,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + Resource + Clone + Default + DeserializeOwned> Plugin for DataPlugin<A> {
+    fn build(&self, app: &mut App) {
+        let path = self.path;
+        app.init_asset::<A>()
+            .register_asset_loader(RonLoader::<A>::default())
+            .init_resource::<A>()
+            .add_systems(
+                Startup,
+                move |asset_server: Res<AssetServer>, mut commands: Commands| {
+                    commands.insert_resource(DataFile::<A>(asset_server.load(path)));
+                },
+            )
+            .add_systems(Update, (data_loaded::<A>, data_failed::<A>));
+    }
+}
+
+fn data_loaded<A: Asset + Resource + Clone>(
+    mut events: MessageReader<AssetEvent<A>>,
+    file: Res<DataFile<A>>,
+    assets: Res<Assets<A>>,
+    mut data: ResMut<A>,
+) {
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event
+            && *id == file.0.id()
+            && let Some(asset) = assets.get(*id)
+        {
+            info!("Data loaded: {}", A::short_type_path());
+            *data = asset.clone();
+        }
+    }
+}
+
+/// Missing and broken data files leave the resource as it was.
+pub fn data_failed<A: Asset>(mut events: MessageReader<AssetLoadFailedEvent<A>>) {
+    for event in events.read() {
+        warn!("Data file {} not loaded: {}", event.path, event.error);
+    }
+}
@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+
+use crate::{
+    components::{Npc, Player},
+    constants::{K_DIALOGUE_DIR, K_INVENTORY_SIZE, K_MAX_CONTRACTS, K_TYPEWRITER_CPS},
+    contracts::{ContractBoard, Contracts},
+    data::{RonLoader, data_failed},
+    events::{EndAction, Talk},
+    items::{self, FishType},
+    resources::Calendar,
+    states::GameState,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Dialogue System
+///
+/// Branching dialogue trees from data files, one per character named after it. Nodes are revealed
+/// like a typewriter, choices are only offered when their conditions hold and both nodes and
+/// choices can have side effects.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    MinMoney(f32),
+    MaxMoney(f32),
+    MinReputation(f32),
+    HasFish(FishType),
+    // A contract with this name is taken
+    Contract(String),
+    NoContract(String),
+    // Another contract can be taken
+    ContractSlot,
+    // The mark was not set today, for things offered once a day
+    NotToday(String),
+}
+
+impl Condition {
+    pub fn holds(
+        &self,
+        player: &Player,
+        contracts: &Contracts,
+        marks: &DialogueMarks,
+        day: u32,
+    ) -> bool {
+        match self {
+            Condition::MinMoney(money) => player.money >= *money,
+            Condition::MaxMoney(money) => player.money <= *money,
+            Condition::MinReputation(reputation) => player.reputation >= *reputation,
+            Condition::HasFish(t) => player
+                .items
+                .iter()
+                .any(|item| matches!(item, items::Item::Fish(fish) if fish.t == *t)),
+            Condition::Contract(name) => contracts.taken(name),
+            Condition::NoContract(name) => !contracts.taken(name),
+            Condition::ContractSlot => contracts.active.len() < K_MAX_CONTRACTS,
+            Condition::NotToday(mark) => marks.days.get(mark) != Some(&day),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    // Money and reputation changes, negative to take
+    Money(f32),
+    Reputation(f32),
+    GiveItem(items::Item),
+    // Takes the contract with this name from the board
+    StartContract(String),
+    // Sets the mark on the day
+    Mark(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Choice {
+    pub text: String,
+    // Node to go to, the dialogue ends without one
+    #[serde(default)]
+    pub next: Option<String>,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueNode {
+    pub speaker: String,
+    pub text: String,
+    #[serde(default)]
+    pub choices: Vec<Choice>,
+    // Applied when the node is reached
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueTree {
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+/// Dialogue trees by name, loaded for the characters as they are spawned.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct DialogueLibrary {
+    pub trees: HashMap<String, DialogueTree>,
+    handles: HashMap<String, Handle<DialogueTree>>,
+}
+
+/// Day each dialogue mark was last set on.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct DialogueMarks {
+    pub days: HashMap<String, u32>,
+}
+
+/// Dialogue in progress.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct ActiveDialogue {
+    pub tree: String,
+    pub node: String,
+    // Characters of the node text shown so far
+    pub revealed: f32,
+    // Index in the available choices
+    pub selected: usize,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct DialogueBox;
+
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DialogueTree>()
+            .register_asset_loader(RonLoader::<DialogueTree>::default())
+            .init_resource::<DialogueLibrary>()
+            .init_resource::<DialogueMarks>()
+            .init_resource::<ActiveDialogue>()
+            .add_observer(on_talk)
+            .add_systems(
+                Update,
+                (
+                    load_dialogues,
+                    dialogues_loaded,
+                    data_failed::<DialogueTree>,
+                ),
+            )
+            .add_systems(
+                Update,
+                (dialogue_input, dialogue_text)
+                    .chain()
+                    .run_if(in_state(GameState::InDialogue)),
+            )
+            .add_systems(OnEnter(GameState::InDialogue), enter_dialogue)
+            .add_systems(OnExit(GameState::InDialogue), exit_dialogue);
+    }
+}
+
+impl ActiveDialogue {
+    pub fn node<'a>(&self, library: &'a DialogueLibrary) -> Option<&'a DialogueNode> {
+        library.trees.get(&self.tree)?.nodes.get(&self.node)
+    }
+}
+
+fn available<'a>(
+    node: &'a DialogueNode,
+    player: &Player,
+    contracts: &Contracts,
+    marks: &DialogueMarks,
+    day: u32,
+) -> Vec<&'a Choice> {
+    node.choices
+        .iter()
+        .filter(|choice| {
+            choice
+                .conditions
+                .iter()
+                .all(|condition| condition.holds(player, contracts, marks, day))
+        })
+        .collect()
+}
+
+fn apply(
+    effects: &[Effect],
+    player: &mut Player,
+    contracts: &mut Contracts,
+    marks: &mut DialogueMarks,
+    board: &ContractBoard,
+    day: u32,
+) {
+    for effect in effects {
+        info!("Dialogue effect: {:?}", effect);
+        match effect {
+            Effect::Money(money) => player.money += money,
+            Effect::Reputation(reputation) => player.add_reputation(*reputation),
+            Effect::GiveItem(items::Item::Bait(bait)) => {
+                if !player.add_bait(bait.t, bait.count) {
+                    info!("Inventory is full");
+                }
+            }
+            Effect::GiveItem(item) => {
+                if player.items.len() < K_INVENTORY_SIZE {
+                    player.items.push(item.clone());
+                } else {
+                    info!("Inventory is full");
+                }
+            }
+            Effect::StartContract(name) => {
+                match board.offers.iter().find(|def| def.name == *name) {
+                    Some(def) => {
+                        contracts.take(def, day);
+                    }
+                    None => info!("Unknown contract: {}", name),
+                }
+            }
+            Effect::Mark(mark) => {
+                marks.days.insert(mark.clone(), day);
+            }
+        }
+    }
+}
+
+/// Loads the dialogue tree of the characters spawned.
+pub fn load_dialogues(
+    asset_server: Res<AssetServer>,
+    npcs: Query<&Npc, Added<Npc>>,
+    mut library: ResMut<DialogueLibrary>,
+) {
+    for npc in npcs {
+        if !library.handles.contains_key(&npc.dialogue) {
+            let path = format!("{}/{}.ron", K_DIALOGUE_DIR, npc.dialogue);
+            let handle = asset_server.load(path);
+            library.handles.insert(npc.dialogue.clone(), handle);
+        }
+    }
+}
+
+pub fn dialogues_loaded(
+    mut events: MessageReader<AssetEvent<DialogueTree>>,
+    assets: Res<Assets<DialogueTree>>,
+    mut library: ResMut<DialogueLibrary>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(name) = library
+            .handles
+            .iter()
+            .find(|(_, handle)| handle.id() == *id)
+            .map(|(name, _)| name.clone())
+        else {
+            continue;
+        };
+        if let Some(tree) = assets.get(*id) {
+            info!("Dialogue loaded: {}", name);
+            library.trees.insert(name, tree.clone());
+        }
+    }
+}
+
+pub fn on_talk(
+    talk: On<Talk>,
+    library: Res<DialogueLibrary>,
+    calendar: Res<Calendar>,
+    board: Res<ContractBoard>,
+    mut contracts: ResMut<Contracts>,
+    mut marks: ResMut<DialogueMarks>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player: Single<&mut Player>,
+    mut commands: Commands,
+) {
+    let Some(tree) = library.trees.get(&talk.dialogue) else {
+        info!("Unknown dialogue: {}", talk.dialogue);
+        return;
+    };
+    // Talking ends the action the player started it from.
+    commands.trigger(EndAction);
+    *dialogue = ActiveDialogue {
+        tree: talk.dialogue.clone(),
+        node: tree.start.clone(),
+        ..default()
+    };
+    if let Some(node) = dialogue.node(&library) {
+        apply(
+            &node.effects,
+            &mut player.into_inner(),
+            &mut contracts,
+            &mut marks,
+            &board,
+            calendar.day(),
+        );
+    }
+    next_state.set(GameState::InDialogue);
+}
+
+pub fn dialogue_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    library: Res<DialogueLibrary>,
+    calendar: Res<Calendar>,
+    board: Res<ContractBoard>,
+    mut contracts: ResMut<Contracts>,
+    mut marks: ResMut<DialogueMarks>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player: Single<&mut Player>,
+) {
+    let Some(node) = dialogue.node(&library) else {
+        next_state.set(GameState::InGame);
+        return;
+    };
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        info!("Dialogue left");
+        next_state.set(GameState::InGame);
+        return;
+    }
+
+    // The game is paused, so the text is revealed on real time.
+    let length = node.text.chars().count() as f32;
+    if dialogue.revealed < length {
+        dialogue.revealed = (dialogue.revealed + K_TYPEWRITER_CPS * time.delta_secs()).min(length);
+    }
+
+    let mut player = player.into_inner();
+    let day = calendar.day();
+    let choices = available(node, &player, &contracts, &marks, day);
+    // Effects may have taken choices away since the last frame.
+    dialogue.selected = dialogue.selected.min(choices.len().saturating_sub(1));
+    if keyboard_input.just_pressed(KeyCode::KeyW) || keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        dialogue.selected = dialogue.selected.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyS) || keyboard_input.just_pressed(KeyCode::ArrowDown)
+    {
+        dialogue.selected = (dialogue.selected + 1).min(choices.len().saturating_sub(1));
+    }
+
+    if !keyboard_input.just_pressed(KeyCode::Enter) && !keyboard_input.just_pressed(KeyCode::Space)
+    {
+        return;
+    }
+    // The first press shows the whole text.
+    if dialogue.revealed < length {
+        dialogue.revealed = length;
+        return;
+    }
+    let Some(choice) = choices.get(dialogue.selected).copied() else {
+        next_state.set(GameState::InGame);
+        return;
+    };
+
+    info!("Dialogue choice: {}", choice.text);
+    apply(
+        &choice.effects,
+        &mut player,
+        &mut contracts,
+        &mut marks,
+        &board,
+        day,
+    );
+    match &choice.next {
+        Some(next) => {
+            dialogue.node = next.clone();
+            dialogue.revealed = 0.0;
+            dialogue.selected = 0;
+            if let Some(node) = dialogue.node(&library) {
+                apply(
+                    &node.effects,
+                    &mut player,
+                    &mut contracts,
+                    &mut marks,
+                    &board,
+                    day,
+                );
+            }
+        }
+        None => next_state.set(GameState::InGame),
+    }
+}
+
+pub fn dialogue_text(
+    library: Res<DialogueLibrary>,
+    dialogue: Res<ActiveDialogue>,
+    calendar: Res<Calendar>,
+    contracts: Res<Contracts>,
+    marks: Res<DialogueMarks>,
+    player: Single<&Player>,
+    text: Single<&mut Text, With<DialogueBox>>,
+) {
+    let Some(node) = dialogue.node(&library) else {
+        return;
+    };
+    let shown: String = node.text.chars().take(dialogue.revealed as usize).collect();
+    let mut lines = vec![format!("{}: {}", node.speaker, shown)];
+
+    // Choices show up once the text is complete.
+    if dialogue.revealed as usize >= node.text.chars().count() {
+        lines.push(String::new());
+        let choices = available(node, &player, &contracts, &marks, calendar.day());
+        for (index, choice) in choices.iter().enumerate() {
+            let marker = if index == dialogue.selected { ">" } else { " " };
+            lines.push(format!("{} {}", marker, choice.text));
+        }
+        if choices.is_empty() {
+            lines.push(String::from("[Enter] Goodbye"));
+        }
+    }
+
+    let content = lines.join("\n");
+    let mut text = text.into_inner();
+    if text.0 != content {
+        text.0 = content;
+    }
+}
+
+pub fn enter_dialogue(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    info!("Creating dialogue box");
+    time.pause();
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(20.0),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(80),
+            min_height: percent(20),
+            bottom: percent(5),
+            left: percent(10),
+            padding: UiRect::all(px(16)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+        DialogueBox,
+    ));
+}
+
+pub fn exit_dialogue(
+    mut commands: Commands,
+    query: Query<Entity, With<DialogueBox>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    info!("Removing dialogue box");
+    for entity in query {
+        commands.entity(entity).despawn();
+    }
+    time.unpause();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::tests::def;
+
+    #[test]
+    fn money_and_reputation_conditions() {
+        let player = Player {
+            money: 10.0,
+            reputation: 1.5,
+            ..default()
+        };
+        let contracts = Contracts::default();
+        let marks = DialogueMarks::default();
+        let holds = |condition: Condition| condition.holds(&player, &contracts, &marks, 1);
+        assert!(holds(Condition::MinMoney(10.0)));
+        assert!(!holds(Condition::MinMoney(10.5)));
+        assert!(holds(Condition::MaxMoney(10.0)));
+        assert!(!holds(Condition::MaxMoney(5.0)));
+        assert!(holds(Condition::MinReputation(1.0)));
+        assert!(!holds(Condition::MinReputation(2.0)));
+    }
+
+    #[test]
+    fn fish_conditions() {
+        let mut player = Player::default();
+        player.items.push(items::Item::Fish(items::Fish {
+            t: FishType::Shark,
+            weight: 40.0,
+            day: 1,
+        }));
+        let contracts = Contracts::default();
+        let marks = DialogueMarks::default();
+        assert!(Condition::HasFish(FishType::Shark).holds(&player, &contracts, &marks, 1));
+        assert!(!Condition::HasFish(FishType::Ray).holds(&player, &contracts, &marks, 1));
+    }
+
+    #[test]
+    fn contract_conditions() {
+        let player = Player::default();
+        let mut contracts = Contracts::default();
+        let marks = DialogueMarks::default();
+        let name = String::from("Ray delivery");
+        assert!(Condition::NoContract(name.clone()).holds(&player, &contracts, &marks, 1));
+        contracts.take(&def(&name, 3, 2), 1);
+        assert!(Condition::Contract(name.clone()).holds(&player, &contracts, &marks, 1));
+        assert!(!Condition::NoContract(name).holds(&player, &contracts, &marks, 1));
+
+        assert!(Condition::ContractSlot.holds(&player, &contracts, &marks, 1));
+        for index in 1..K_MAX_CONTRACTS {
+            contracts.take(&def(&format!("Other {}", index), 3, 2), 1);
+        }
+        assert!(!Condition::ContractSlot.holds(&player, &contracts, &marks, 1));
+    }
+
+    #[test]
+    fn marks_hold_for_the_day() {
+        let mut player = Player::default();
+        let mut contracts = Contracts::default();
+        let mut marks = DialogueMarks::default();
+        let board = ContractBoard::default();
+        let bait = Condition::NotToday(String::from("bait"));
+        assert!(bait.holds(&player, &contracts, &marks, 3));
+        apply(
+            &[Effect::Mark(String::from("bait"))],
+            &mut player,
+            &mut contracts,
+            &mut marks,
+            &board,
+            3,
+        );
+        assert!(!bait.holds(&player, &contracts, &marks, 3));
+        assert!(bait.holds(&player, &contracts, &marks, 4));
+    }
+
+    #[test]
+    fn dialogue_data_file_parses() {
+        let tree: DialogueTree =
+            ron::from_str(include_str!("../assets/data/dialogue/harbour_master.ron")).unwrap();
+        assert!(tree.nodes.contains_key(&tree.start));
+        for node in tree.nodes.values() {
+            for next in node
+                .choices
+                .iter()
+                .filter_map(|choice| choice.next.as_ref())
+            {
+                assert!(tree.nodes.contains_key(next));
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::{items, resources::Milestone};
+
 /// Events
 
 #[derive(Event)]
@@ -19,3 +21,42 @@ pub struct Hit;
 
 #[derive(Event)]
 pub struct Sell;
+
+/// Fish sold at the hut in one go.
+#[derive(Event)]
+pub struct Sold {
+    pub fish: Vec<items::Fish>,
+}
+
+#[derive(Event)]
+pub struct Inspection;
+
+/// Releases the fish at the inventory index back into the sea.
+#[derive(Event)]
+pub struct Release {
+    pub index: usize,
+}
+
+/// A fish was let go, `juvenile` when under the minimum weight.
+#[derive(Event)]
+pub struct Released {
+    pub fish: items::Fish,
+    pub juvenile: bool,
+}
+
+/// Starts the named dialogue tree.
+#[derive(Event)]
+pub struct Talk {
+    pub dialogue: String,
+}
+
+#[derive(Event)]
+pub struct Story {
+    pub milestone: Milestone,
+}
+
+/// A fish was landed, for the records book.
+#[derive(Event)]
+pub struct Landed {
+    pub fish: items::Fish,
+}
@@ -1,4 +1,9 @@
 /// Item System
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::K_DAYS_IN_SEASON;
 
 pub trait Weight {
     fn weight(&self) -> f32;
@@ -9,21 +14,24 @@ pub trait Value {
     fn value(&self) -> f32;
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Item {
     Fish(Fish),
+    Bait(Bait),
 }
 
 impl Value for Item {
     fn name(&self) -> String {
         match self {
             Item::Fish(fish) => fish.name(),
+            Item::Bait(bait) => bait.name(),
         }
     }
 
     fn value(&self) -> f32 {
         match self {
             Item::Fish(fish) => fish.value(),
+            Item::Bait(bait) => bait.value(),
         }
     }
 }
@@ -32,11 +40,44 @@ impl Weight for Item {
     fn weight(&self) -> f32 {
         match self {
             Item::Fish(fish) => fish.weight,
+            Item::Bait(bait) => bait.weight(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn from_day(day: u32) -> Self {
+        match (day / K_DAYS_IN_SEASON) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Hours of the day from `start` to `end`, wrapping past midnight when `start > end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hours {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Hours {
+    pub fn text(&self) -> String {
+        format!("{:02.0}:00-{:02.0}:00", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FishType {
     Fish,
     Ray,
@@ -61,10 +102,101 @@ impl Value for FishType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl FishType {
+    pub const ALL: [FishType; 3] = [FishType::Fish, FishType::Ray, FishType::Shark];
+
+    /// Most fish of the species alive at once.
+    pub fn max_population(&self) -> usize {
+        match self {
+            FishType::Fish => 160,
+            FishType::Ray => 64,
+            FishType::Shark => 16,
+        }
+    }
+
+    /// Weight range of a caught fish in kg.
+    pub fn weight(&self) -> Range<f32> {
+        match self {
+            FishType::Fish => 0.2..2.0,
+            FishType::Ray => 1.0..6.0,
+            FishType::Shark => 5.0..40.0,
+        }
+    }
+
+    /// Preferred depth band below the water surface.
+    pub fn depth(&self) -> Range<f32> {
+        match self {
+            FishType::Fish => 8.0..64.0,
+            FishType::Ray => 48.0..160.0,
+            FishType::Shark => 112.0..224.0,
+        }
+    }
+
+    /// Hours of the day the species is said to feed, journal lore without effect on catches.
+    pub fn active_hours(&self) -> Hours {
+        match self {
+            FishType::Fish => Hours {
+                start: 5.0,
+                end: 20.0,
+            },
+            FishType::Ray => Hours {
+                start: 16.0,
+                end: 4.0,
+            },
+            FishType::Shark => Hours {
+                start: 19.0,
+                end: 7.0,
+            },
+        }
+    }
+
+    /// Season the species is said to bite best, journal lore like `active_hours`.
+    pub fn season(&self) -> Season {
+        match self {
+            FishType::Fish => Season::Spring,
+            FishType::Ray => Season::Summer,
+            FishType::Shark => Season::Autumn,
+        }
+    }
+
+    /// Whether the species swims in schools of its own kind.
+    pub fn schools(&self) -> bool {
+        match self {
+            FishType::Fish => true,
+            FishType::Ray => false,
+            FishType::Shark => false,
+        }
+    }
+
+    /// Species hunted by this one.
+    pub fn prey(&self) -> &'static [FishType] {
+        match self {
+            FishType::Fish => &[],
+            FishType::Ray => &[],
+            FishType::Shark => &[FishType::Fish, FishType::Ray],
+        }
+    }
+
+    /// How strongly the species is drawn to a still hook, in [0, 1].
+    pub fn curiosity(&self) -> f32 {
+        match self {
+            FishType::Fish => 1.0,
+            FishType::Ray => 0.6,
+            FishType::Shark => 0.3,
+        }
+    }
+
+    pub fn is_predator(&self) -> bool {
+        !self.prey().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Fish {
     pub t: FishType,
     pub weight: f32,
+    // In-game day of the catch
+    pub day: u32,
 }
 
 impl Value for Fish {
@@ -76,3 +208,96 @@ impl Value for Fish {
         self.weight * self.t.value()
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BaitType {
+    Worm,
+    Shrimp,
+    FishChunk,
+}
+
+impl Value for BaitType {
+    fn name(&self) -> String {
+        match self {
+            BaitType::Worm => String::from("Worm"),
+            BaitType::Shrimp => String::from("Shrimp"),
+            BaitType::FishChunk => String::from("Fish Chunk"),
+        }
+    }
+
+    // Price of one piece at the hut
+    fn value(&self) -> f32 {
+        match self {
+            BaitType::Worm => 0.25,
+            BaitType::Shrimp => 0.5,
+            BaitType::FishChunk => 0.25,
+        }
+    }
+}
+
+impl BaitType {
+    /// Bite chance multiplier for the species.
+    pub fn attraction(&self, fish: &FishType) -> f32 {
+        match (self, fish) {
+            (BaitType::Worm, FishType::Fish) => 2.0,
+            (BaitType::Worm, FishType::Ray) => 1.0,
+            (BaitType::Worm, FishType::Shark) => 0.5,
+            (BaitType::Shrimp, FishType::Fish) => 1.0,
+            (BaitType::Shrimp, FishType::Ray) => 2.0,
+            (BaitType::Shrimp, FishType::Shark) => 1.0,
+            (BaitType::FishChunk, FishType::Fish) => 0.5,
+            (BaitType::FishChunk, FishType::Ray) => 1.0,
+            (BaitType::FishChunk, FishType::Shark) => 3.0,
+        }
+    }
+
+    /// Seconds the baited hook has to stay still before fish come to it.
+    pub fn lure_secs(&self) -> f32 {
+        match self {
+            BaitType::Worm => 1.0,
+            BaitType::Shrimp => 1.5,
+            BaitType::FishChunk => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bait {
+    pub t: BaitType,
+    pub count: u32,
+}
+
+impl Value for Bait {
+    fn name(&self) -> String {
+        format!("Bait - {} x{}", self.t.name(), self.count)
+    }
+
+    fn value(&self) -> f32 {
+        self.count as f32 * self.t.value()
+    }
+}
+
+impl Weight for Bait {
+    fn weight(&self) -> f32 {
+        0.05 * self.count as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hours_text() {
+        assert_eq!(FishType::Fish.active_hours().text(), "05:00-20:00");
+        assert_eq!(FishType::Ray.active_hours().text(), "16:00-04:00");
+    }
+
+    #[test]
+    fn seasons_follow_the_days() {
+        assert_eq!(Season::from_day(0), Season::Spring);
+        assert_eq!(Season::from_day(K_DAYS_IN_SEASON), Season::Summer);
+        assert_eq!(Season::from_day(3 * K_DAYS_IN_SEASON), Season::Winter);
+        assert_eq!(Season::from_day(4 * K_DAYS_IN_SEASON), Season::Spring);
+    }
+}
@@ -1,14 +1,54 @@
-use crate::{
-    components::{
-        self, ActionRange, ActiveSprite, AnimationConfig, AnimationTimer, Boat, Building, Cloud,
-        DayNightColor, DefaultColor, Direction, Fish, Land, Layer, Ocean, OnAI, OnControl, Player,
-        PlayerState, Sky, SpriteCollection, Sun, Velocity,
-    },
-    constants::K_FISH_CATCH_RANGE,
-};
 use bevy::prelude::*;
 
 /// Layer System
+///
+/// Layers of objects built from descriptions. The game marks each layer with its own component
+/// and adds its components to the objects through `LayerComponent`.
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub depth: f32,
+    pub speed: f32,
+    pub size: Vec2,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct DefaultColor {
+    pub color: Color,
+}
+
+#[derive(Default, Component, Debug, Clone, PartialEq)]
+pub struct AnimationTimer {
+    pub timer: Timer,
+}
+
+/// Event `E` fired when the animation reaches the given atlas index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameEvent<E> {
+    pub index: usize,
+    pub event: E,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct AnimationConfig<E: Send + Sync + 'static> {
+    pub first_index: usize,
+    pub last_index: usize,
+    // Duration per frame in ms
+    pub ms: u64,
+    pub mode: TimerMode,
+    pub events: Vec<FrameEvent<E>>,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct SpriteCollection<E: Send + Sync + 'static> {
+    pub sprites: Vec<Sprite>,
+    pub animations: Vec<AnimationConfig<E>>,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct ActiveSprite {
+    pub index: usize,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PrimitiveType {
@@ -23,7 +63,7 @@ pub struct SpriteDesc {
 }
 
 #[derive(Default, Debug, Clone, PartialEq)]
-pub struct SpriteAtlasDesc {
+pub struct SpriteAtlasDesc<E> {
     pub sprite: SpriteDesc,
     pub tile: UVec2,
     pub rows: u32,
@@ -31,90 +71,100 @@ pub struct SpriteAtlasDesc {
     pub index: usize,
     pub mode: TimerMode,
     pub ms: u64,
+    pub events: Vec<FrameEvent<E>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum ObjectType {
+pub enum ObjectType<E> {
     Primitive(PrimitiveType),
     Sprite(SpriteDesc),
-    SpriteAtlas(SpriteAtlasDesc),
-    SpriteCollection(Vec<SpriteAtlasDesc>),
+    SpriteAtlas(SpriteAtlasDesc<E>),
+    SpriteCollection(Vec<SpriteAtlasDesc<E>>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ObjectComponentType {
-    Player,
-    Boat,
-    Land,
-    Ocean,
-    Building,
-    Sun,
-    Cloud(Cloud),
-    Sky,
-    Fish(Fish),
+/// Components attached to layer objects by the game using the layer system.
+pub trait LayerComponent {
+    // Events fired on animation frames
+    type Event: Clone + PartialEq + std::fmt::Debug + Send + Sync + 'static;
+
+    fn insert(&self, entity: &mut EntityCommands, size: Vec2);
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct LayerObjectDesc {
-    pub t: ObjectType,
-    pub component: ObjectComponentType,
+pub struct LayerObjectDesc<C: LayerComponent> {
+    pub t: ObjectType<C::Event>,
+    pub component: C,
     pub position: Vec2,
     pub size: Vec2,
     pub color: Color,
     pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum LayerType {
-    Player,
-    Boat,
-    City,
-    Sky,
+impl<C: LayerComponent> LayerObjectDesc<C> {
+    /// Sprite and animation of a sprite atlas object, to spawn copies of it outside of layer builds.
+    pub fn create_sprite_atlas(
+        &self,
+        asset_server: &Res<AssetServer>,
+        texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    ) -> Option<(Sprite, AnimationConfig<C::Event>)> {
+        match &self.t {
+            ObjectType::SpriteAtlas(atlas) => Some(create_sprite_atlas(
+                asset_server,
+                texture_atlas_layouts,
+                atlas,
+                self.size,
+                self.color,
+            )),
+            _ => None,
+        }
+    }
 }
 
-pub struct LayerDesc {
-    pub t: LayerType,
-    pub objects: Vec<LayerObjectDesc>,
+/// Layer of objects, `marker` is the component marking the layer entity.
+pub struct LayerDesc<C: LayerComponent, M: Component + Clone> {
+    pub marker: M,
+    pub objects: Vec<LayerObjectDesc<C>>,
     pub depth: f32,
     pub speed: f32,
     pub size: Vec2,
     pub name: String,
 }
 
-impl LayerDesc {
-    fn create_sprite_atlas_entity(
-        asset_server: &Res<AssetServer>,
-        texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-        atlas: &SpriteAtlasDesc,
-        size: Vec2,
-        color: Color,
-    ) -> (Sprite, AnimationConfig) {
-        let texture = asset_server.load(atlas.sprite.path.clone());
-        let layout = TextureAtlasLayout::from_grid(atlas.tile, atlas.cols, atlas.rows, None, None);
-        let texture_atlas_layout = texture_atlas_layouts.add(layout);
-
-        let sprite = Sprite {
-            image: texture,
-            texture_atlas: Some(TextureAtlas {
-                layout: texture_atlas_layout,
-                index: atlas.index,
-            }),
-            custom_size: Some(Vec2::new(size.x, size.y)),
-            color: color,
-            image_mode: atlas.sprite.mode.clone(),
-            ..default()
-        };
-
-        let animation_config = AnimationConfig {
-            first_index: atlas.index,
-            last_index: std::cmp::max(atlas.cols - 1, atlas.rows - 1) as usize,
-            ms: atlas.ms,
-            mode: atlas.mode,
-        };
-
-        (sprite, animation_config)
-    }
+fn create_sprite_atlas<E: Clone + Send + Sync + 'static>(
+    asset_server: &Res<AssetServer>,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    atlas: &SpriteAtlasDesc<E>,
+    size: Vec2,
+    color: Color,
+) -> (Sprite, AnimationConfig<E>) {
+    let texture = asset_server.load(atlas.sprite.path.clone());
+    let layout = TextureAtlasLayout::from_grid(atlas.tile, atlas.cols, atlas.rows, None, None);
+    let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
+    let sprite = Sprite {
+        image: texture,
+        texture_atlas: Some(TextureAtlas {
+            layout: texture_atlas_layout,
+            index: atlas.index,
+        }),
+        custom_size: Some(Vec2::new(size.x, size.y)),
+        color: color,
+        image_mode: atlas.sprite.mode.clone(),
+        ..default()
+    };
+
+    let animation_config = AnimationConfig {
+        first_index: atlas.index,
+        last_index: std::cmp::max(atlas.cols - 1, atlas.rows - 1) as usize,
+        ms: atlas.ms,
+        mode: atlas.mode,
+        events: atlas.events.clone(),
+    };
+
+    (sprite, animation_config)
+}
+
+impl<C: LayerComponent, M: Component + Clone> LayerDesc<C, M> {
     pub fn build(
         &self,
         commands: &mut Commands,
@@ -123,22 +173,18 @@ impl LayerDesc {
         texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
         materials: &mut ResMut<Assets<ColorMaterial>>,
     ) {
-        let mut layer_entity = commands.spawn((
-            Transform::from_xyz(0.0, 0.0, self.depth),
-            Name::new(self.name.clone()),
-            Layer {
-                depth: self.depth,
-                speed: self.speed,
-                size: self.size,
-            },
-        ));
-
-        let layer_entity = match self.t {
-            LayerType::Player => layer_entity.insert(components::PlayerLayer).id(),
-            LayerType::Boat => layer_entity.insert(components::BoatLayer).id(),
-            LayerType::City => layer_entity.insert(components::CityLayer).id(),
-            LayerType::Sky => layer_entity.insert(components::SkyLayer).id(),
-        };
+        let layer_entity = commands
+            .spawn((
+                Transform::from_xyz(0.0, 0.0, self.depth),
+                Name::new(self.name.clone()),
+                Layer {
+                    depth: self.depth,
+                    speed: self.speed,
+                    size: self.size,
+                },
+                self.marker.clone(),
+            ))
+            .id();
 
         for obj in &self.objects {
             let entity_id = match &obj.t {
@@ -184,7 +230,7 @@ impl LayerDesc {
                         .id()
                 }
                 ObjectType::SpriteAtlas(atlas) => {
-                    let (sprite, animation_config) = Self::create_sprite_atlas_entity(
+                    let (sprite, animation_config) = create_sprite_atlas(
                         asset_server,
                         texture_atlas_layouts,
                         atlas,
@@ -215,7 +261,7 @@ impl LayerDesc {
                         animations: Vec::new(),
                     };
                     for atlas in collection {
-                        let (sprite, animation_config) = Self::create_sprite_atlas_entity(
+                        let (sprite, animation_config) = create_sprite_atlas(
                             asset_server,
                             texture_atlas_layouts,
                             atlas,
@@ -242,75 +288,9 @@ impl LayerDesc {
                 }
             };
 
-            // Add the specific component type
-            match &obj.component {
-                ObjectComponentType::Player => {
-                    commands
-                        .entity(entity_id)
-                        .insert(Player {
-                            money: 0.0,
-                            items: Vec::new(),
-                        })
-                        .insert(Direction::Right)
-                        .insert(PlayerState::Walk)
-                        .insert(Velocity { ..default() })
-                        .insert(OnControl);
-                }
-                ObjectComponentType::Boat => {
-                    commands
-                        .entity(entity_id)
-                        .insert(Boat)
-                        .insert(Velocity { ..default() })
-                        .insert(DayNightColor);
-                }
-                ObjectComponentType::Land => {
-                    commands.entity(entity_id).insert(Land {
-                        size: obj.size.clone(),
-                    });
-                }
-                ObjectComponentType::Ocean => {
-                    commands
-                        .entity(entity_id)
-                        .insert(Ocean {
-                            size: obj.size.clone(),
-                        })
-                        .insert(ActionRange {
-                            range: obj.size.x / 2.0,
-                        });
-                }
-                ObjectComponentType::Building => {
-                    commands
-                        .entity(entity_id)
-                        .insert(Building)
-                        .insert(ActionRange {
-                            range: obj.size.x / 2.0,
-                        })
-                        .insert(DayNightColor);
-                }
-                ObjectComponentType::Sun => {
-                    commands.entity(entity_id).insert(Sun);
-                }
-                ObjectComponentType::Cloud(cloud) => {
-                    commands
-                        .entity(entity_id)
-                        .insert(cloud.clone())
-                        .insert(DayNightColor);
-                }
-                ObjectComponentType::Sky => {
-                    commands.entity(entity_id).insert(Sky).insert(DayNightColor);
-                }
-                ObjectComponentType::Fish(fish) => {
-                    commands
-                        .entity(entity_id)
-                        .insert(fish.clone())
-                        .insert(Direction::Right)
-                        .insert(Velocity { value: 1.0 })
-                        .insert(ActionRange {
-                            range: K_FISH_CATCH_RANGE,
-                        })
-                        .insert(OnAI);
-                }
-            }
+            // Add the game-specific components
+            obj.component
+                .insert(&mut commands.entity(entity_id), obj.size);
 
             commands.entity(layer_entity).add_child(entity_id);
         }
@@ -1,23 +1,36 @@
+use std::ops::Range;
 use std::time::Duration;
 
-use bevy::{prelude::*, window::WindowResolution};
+use bevy::{camera::ScalingMode, prelude::*, window::WindowResolution};
 
+mod achievements;
+mod ai;
 mod components;
 mod constants;
+mod contracts;
+mod data;
+mod dialogue;
 mod events;
 mod items;
 mod layer;
+mod objects;
+mod plugins;
 mod resources;
+mod save;
 mod states;
 mod systems;
 
 use constants::*;
 use layer::*;
-use states::*;
+use objects::*;
+use plugins::*;
 
-use crate::components::{Cloud, Fish, OnControl, Velocity};
-use crate::items::Value;
-use crate::resources::AITimer;
+use crate::components::{
+    AnimationEvent, BoatLayer, CameraController, CityLayer, Cloud, FaunaLayer, Fish, FrameEvent,
+    Npc, PlayerLayer, ProtectedArea, Protection, SkyLayer, SpawnZone,
+};
+use crate::items::{FishType, Value};
+use crate::resources::{FishSpecies, SpeciesDef, ViewportConfig};
 
 fn main() {
     App::new()
@@ -30,78 +43,12 @@ fn main() {
             ..Default::default()
         }))
         .insert_resource(Time::<Virtual>::from_max_delta(Duration::from_secs(1)))
-        .insert_resource(AITimer {
-            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
-        })
-        .init_state::<GameState>()
-        // Observers.
-        .add_observer(systems::on_action)
-        .add_observer(systems::on_end_action)
-        .add_observer(systems::on_hook)
-        .add_observer(systems::on_sell)
-        .add_observer(systems::on_catch)
-        .add_observer(systems::on_hit)
+        .add_plugins(GamePlugin)
         .add_systems(Startup, setup)
-        // In-action update systems.
         .add_systems(
-            Update,
-            (
-                systems::ai_timer,
-                systems::fish_spawn,
-                systems::added_animation,
-                systems::action_input,
-                systems::ai_input,
-                systems::player_state_walk_or_row,
-                systems::move_control,
-                systems::move_layer,
-                systems::move_sun,
-                systems::move_cloud,
-                systems::move_ai,
-                systems::changed_animation_player,
-                systems::changed_active_sprite,
-                systems::changed_direction,
-                systems::changed_player_state,
-                systems::action_animation_control,
-                systems::animation_ai,
-                systems::animation,
-                systems::color_day_night,
-            )
-                .chain()
-                .run_if(in_state(GameState::InAction)),
+            OnEnter(states::GameState::Restart),
+            setup.after(systems::restart).before(systems::apply_load),
         )
-        // In-game update systems.
-        .add_systems(
-            Update,
-            (
-                systems::ai_timer,
-                systems::fish_spawn,
-                systems::added_animation,
-                systems::game_input,
-                systems::ai_input,
-                systems::player_state_walk_or_row,
-                systems::move_control,
-                systems::move_layer,
-                systems::move_sun,
-                systems::move_cloud,
-                systems::move_ai,
-                systems::changed_active_sprite,
-                systems::changed_direction,
-                systems::changed_player_state,
-                systems::game_animation_control,
-                systems::animation_ai,
-                systems::animation,
-                systems::color_day_night,
-            )
-                .chain()
-                .run_if(in_state(GameState::InGame)),
-        )
-        // In-Menu systems.
-        .add_systems(
-            Update,
-            systems::menu_input.run_if(in_state(GameState::InPlayerMenu)),
-        )
-        .add_systems(OnEnter(GameState::InPlayerMenu), systems::enter_player_menu)
-        .add_systems(OnExit(GameState::InPlayerMenu), systems::exit_player_menu)
         .run();
 }
 
@@ -111,79 +58,165 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    viewport: Res<ViewportConfig>,
 ) {
+    let ground_level = viewport.ground_level;
     commands
         .spawn(Camera2d)
-        .insert(Velocity { ..default() })
-        .insert(OnControl);
+        .insert(Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: K_WIDTH,
+                min_height: K_HEIGHT,
+            },
+            ..OrthographicProjection::default_2d()
+        }))
+        .insert(CameraController {
+            damping: 4.0,
+            look_ahead: 128.0,
+            dive: -K_HEIGHT * 0.2,
+            fish_zoom: 1.25,
+            zoom_damping: 2.0,
+        });
+
+    // Species definitions, fish are spawned from them by the spawn zones.
+    let species = vec![
+        LayerObjectDesc {
+            t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
+                sprite: SpriteDesc {
+                    path: "fauna/fish/7.png".to_string(),
+                    ..default()
+                },
+                tile: UVec2::new(30, 12),
+                cols: 2,
+                rows: 1,
+                index: 0,
+                mode: TimerMode::Repeating,
+                ms: K_ANIMATION_FRAME_MS,
+                events: Vec::new(),
+            }),
+            component: ObjectComponentType::Fish(Fish { t: FishType::Fish }),
+            position: Vec2::ZERO,
+            size: Vec2::new(48.0, 16.0),
+            color: Color::srgb(1.0, 1.0, 1.0),
+            name: FishType::Fish.name(),
+        },
+        LayerObjectDesc {
+            t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
+                sprite: SpriteDesc {
+                    path: "fauna/fish/8.png".to_string(),
+                    ..default()
+                },
+                tile: UVec2::new(30, 12),
+                cols: 2,
+                rows: 1,
+                index: 0,
+                mode: TimerMode::Repeating,
+                ms: K_ANIMATION_FRAME_MS,
+                events: Vec::new(),
+            }),
+            component: ObjectComponentType::Fish(Fish { t: FishType::Ray }),
+            position: Vec2::ZERO,
+            size: Vec2::new(64.0, 18.0),
+            color: Color::srgb(1.0, 1.0, 1.0),
+            name: FishType::Ray.name(),
+        },
+        LayerObjectDesc {
+            t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
+                sprite: SpriteDesc {
+                    path: "fauna/fish/6.png".to_string(),
+                    ..default()
+                },
+                tile: UVec2::new(54, 22),
+                cols: 2,
+                rows: 1,
+                index: 0,
+                mode: TimerMode::Repeating,
+                ms: K_ANIMATION_FRAME_MS,
+                events: Vec::new(),
+            }),
+            component: ObjectComponentType::Fish(Fish { t: FishType::Shark }),
+            position: Vec2::ZERO,
+            size: Vec2::new(128.0, 48.0),
+            color: Color::srgb(1.0, 1.0, 1.0),
+            name: FishType::Shark.name(),
+        },
+    ];
+    commands.insert_resource(FishSpecies {
+        species: species
+            .iter()
+            .filter_map(|desc| {
+                let ObjectComponentType::Fish(fish) = &desc.component else {
+                    return None;
+                };
+                let (sprite, animation) =
+                    desc.create_sprite_atlas(&asset_server, &mut texture_atlas_layouts)?;
+                Some(SpeciesDef {
+                    t: fish.t,
+                    sprite,
+                    animation,
+                    size: desc.size,
+                })
+            })
+            .collect(),
+    });
 
     let layer_fauna = LayerDesc {
         objects: vec![
-            LayerObjectDesc {
-                t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
-                    sprite: SpriteDesc {
-                        path: "fauna/fish/7.png".to_string(),
-                        ..default()
-                    },
-                    tile: UVec2::new(30, 12),
-                    cols: 2,
-                    rows: 1,
-                    index: 0,
-                    mode: TimerMode::Repeating,
-                    ms: K_ANIMATION_FRAME_MS,
-                }),
-                component: ObjectComponentType::Fish(Fish {
-                    t: items::FishType::Fish,
-                }),
-                position: Vec2::new(K_FISH_AREA_BORDER + 128.0, K_GROUND_LEVEL - 16.0),
-                size: Vec2::new(48.0, 16.0),
-                color: Color::srgb(1.0, 1.0, 1.0),
-                name: items::FishType::Fish.name(),
-            },
-            LayerObjectDesc {
-                t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
-                    sprite: SpriteDesc {
-                        path: "fauna/fish/8.png".to_string(),
-                        ..default()
-                    },
-                    tile: UVec2::new(30, 12),
-                    cols: 2,
-                    rows: 1,
-                    index: 0,
-                    mode: TimerMode::Repeating,
-                    ms: K_ANIMATION_FRAME_MS,
-                }),
-                component: ObjectComponentType::Fish(Fish {
-                    t: items::FishType::Ray,
-                }),
-                position: Vec2::new(K_FISH_AREA_BORDER + 128.0, K_GROUND_LEVEL - 64.0),
-                size: Vec2::new(64.0, 18.0),
-                color: Color::srgb(1.0, 1.0, 1.0),
-                name: items::FishType::Ray.name(),
-            },
-            LayerObjectDesc {
-                t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
-                    sprite: SpriteDesc {
-                        path: "fauna/fish/6.png".to_string(),
-                        ..default()
-                    },
-                    tile: UVec2::new(54, 22),
-                    cols: 2,
-                    rows: 1,
-                    index: 0,
-                    mode: TimerMode::Repeating,
-                    ms: K_ANIMATION_FRAME_MS,
-                }),
-                component: ObjectComponentType::Fish(Fish {
-                    t: items::FishType::Shark,
-                }),
-                position: Vec2::new(K_FISH_AREA_BORDER + 128.0, K_GROUND_LEVEL - 128.0),
-                size: Vec2::new(128.0, 48.0),
-                color: Color::srgb(1.0, 1.0, 1.0),
-                name: items::FishType::Shark.name(),
-            },
+            spawn_zone_desc(
+                ground_level,
+                "Shallows",
+                K_FISH_AREA_BORDER..K_FISH_AREA_BORDER + K_FISH_AREA_SIZE / 2.0,
+                vec![(FishType::Fish, 1.0)],
+                1.0,
+                48,
+            ),
+            spawn_zone_desc(
+                ground_level,
+                "Reef",
+                K_FISH_AREA_BORDER..K_FISH_AREA_BORDER + K_FISH_AREA_SIZE,
+                vec![(FishType::Fish, 0.5), (FishType::Ray, 1.0)],
+                0.5,
+                24,
+            ),
+            protected_area_desc(
+                ground_level,
+                "Shallows Reserve",
+                K_FISH_AREA_BORDER + 3.0 * K_FISH_AREA_SIZE / 16.0
+                    ..K_FISH_AREA_BORDER + 5.0 * K_FISH_AREA_SIZE / 16.0,
+                Protection::Limited,
+                1.5,
+            ),
+            protected_area_desc(
+                ground_level,
+                "Reserve",
+                K_FISH_AREA_BORDER + 3.0 * K_FISH_AREA_SIZE / 8.0
+                    ..K_FISH_AREA_BORDER + 5.0 * K_FISH_AREA_SIZE / 8.0,
+                Protection::Banned,
+                3.0,
+            ),
+            spawn_zone_desc(
+                ground_level,
+                "Nursery",
+                K_FISH_AREA_BORDER + 7.0 * K_FISH_AREA_SIZE / 16.0
+                    ..K_FISH_AREA_BORDER + 9.0 * K_FISH_AREA_SIZE / 16.0,
+                vec![
+                    (FishType::Fish, 1.0),
+                    (FishType::Ray, 0.5),
+                    (FishType::Shark, 0.1),
+                ],
+                0.2,
+                16,
+            ),
+            spawn_zone_desc(
+                ground_level,
+                "Deep",
+                K_FISH_AREA_BORDER + K_FISH_AREA_SIZE / 2.0..K_FISH_AREA_BORDER + K_FISH_AREA_SIZE,
+                vec![(FishType::Ray, 0.5), (FishType::Shark, 1.0)],
+                0.1,
+                6,
+            ),
         ],
-        t: LayerType::City,
+        marker: FaunaLayer,
         depth: 1.0,
         speed: 0.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -197,7 +230,7 @@ fn setup(
                 component: ObjectComponentType::Land,
                 position: Vec2::new(
                     K_OCEAN_LAND_BORDER - K_LAND_SIZE / 2.0,
-                    K_GROUND_LEVEL - K_HEIGHT / 2.0,
+                    ground_level - K_HEIGHT / 2.0,
                 ),
                 size: Vec2::new(K_LAND_SIZE, K_HEIGHT),
                 color: Color::srgb_u8(60, 128, 60),
@@ -208,14 +241,14 @@ fn setup(
                 component: ObjectComponentType::Ocean,
                 position: Vec2::new(
                     K_OCEAN_LAND_BORDER + K_OCEAN_SIZE / 2.0,
-                    K_GROUND_LEVEL - K_HEIGHT / 2.0,
+                    ground_level - K_HEIGHT / 2.0,
                 ),
                 size: Vec2::new(K_OCEAN_SIZE, K_HEIGHT),
                 color: Color::srgb_u8(85, 128, 200),
                 name: "Ocean".to_string(),
             },
         ],
-        t: LayerType::City,
+        marker: CityLayer,
         depth: 1.0,
         speed: 0.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -223,18 +256,42 @@ fn setup(
     };
 
     let layer_city = LayerDesc {
-        objects: vec![LayerObjectDesc {
-            t: ObjectType::Sprite(SpriteDesc {
-                path: "building/hut.png".to_string(),
-                ..default()
-            }),
-            component: ObjectComponentType::Building,
-            position: Vec2::new(K_OCEAN_LAND_BORDER, K_GROUND_LEVEL + 128.0 - 66.0),
-            size: Vec2::new(480.0, 320.0),
-            color: Color::srgb(1.0, 1.0, 1.0),
-            name: "Hut".to_string(),
-        }],
-        t: LayerType::City,
+        objects: vec![
+            LayerObjectDesc {
+                t: ObjectType::Sprite(SpriteDesc {
+                    path: "building/hut.png".to_string(),
+                    ..default()
+                }),
+                component: ObjectComponentType::Building,
+                position: Vec2::new(K_OCEAN_LAND_BORDER, ground_level + 128.0 - 66.0),
+                size: Vec2::new(480.0, 320.0),
+                color: Color::srgb(1.0, 1.0, 1.0),
+                name: "Hut".to_string(),
+            },
+            LayerObjectDesc {
+                t: ObjectType::SpriteAtlas(SpriteAtlasDesc {
+                    sprite: SpriteDesc {
+                        path: "player/idle.png".to_string(),
+                        ..default()
+                    },
+                    tile: UVec2::splat(48),
+                    cols: 4,
+                    rows: 1,
+                    index: 0,
+                    mode: TimerMode::Repeating,
+                    ms: K_ANIMATION_FRAME_MS,
+                    events: Vec::new(),
+                }),
+                component: ObjectComponentType::Npc(Npc {
+                    dialogue: "harbour_master".to_string(),
+                }),
+                position: Vec2::new(K_OCEAN_LAND_BORDER - 384.0, ground_level + 64.0),
+                size: Vec2::new(128.0, 128.0),
+                color: Color::srgb(0.7, 0.8, 1.0),
+                name: "Harbour Master".to_string(),
+            },
+        ],
+        marker: CityLayer,
         depth: 0.0,
         speed: 0.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -245,12 +302,12 @@ fn setup(
         objects: vec![LayerObjectDesc {
             t: ObjectType::Primitive(PrimitiveType::Circle),
             component: ObjectComponentType::Sun,
-            position: Vec2::new(0.0, K_GROUND_LEVEL + 512.0),
+            position: Vec2::new(0.0, ground_level + 512.0),
             size: Vec2::new(128.0, 128.0),
             color: Color::srgb(1.0, 1.0, 0.0),
             name: "Sun".to_string(),
         }],
-        t: LayerType::Sky,
+        marker: SkyLayer,
         depth: -9.0,
         speed: 1.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -269,7 +326,7 @@ fn setup(
             color: Color::srgb(1.0, 1.0, 1.0),
             name: "Sky".to_string(),
         }],
-        t: LayerType::Sky,
+        marker: SkyLayer,
         depth: -10.0,
         speed: 1.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -292,7 +349,7 @@ fn setup(
             color: Color::srgb(1.0, 1.0, 1.0),
             name: "Mountain".to_string(),
         }],
-        t: LayerType::Sky,
+        marker: SkyLayer,
         depth: -8.0,
         speed: 0.75,
         size: Vec2::new(8.0 * K_WIDTH, K_HEIGHT),
@@ -347,7 +404,7 @@ fn setup(
                 name: "Clouds-1".to_string(),
             },
         ],
-        t: LayerType::Sky,
+        marker: SkyLayer,
         depth: -5.0,
         speed: 1.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -370,7 +427,7 @@ fn setup(
             color: Color::srgb(1.0, 1.0, 1.0),
             name: "Forest".to_string(),
         }],
-        t: LayerType::Sky,
+        marker: SkyLayer,
         depth: -4.0,
         speed: 0.5,
         size: Vec2::new(8.0 * K_WIDTH, K_HEIGHT),
@@ -391,6 +448,7 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Repeating,
                     ms: K_ANIMATION_FRAME_MS,
+                    events: Vec::new(),
                 },
                 SpriteAtlasDesc {
                     sprite: SpriteDesc {
@@ -403,6 +461,7 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Repeating,
                     ms: K_ANIMATION_FRAME_MS,
+                    events: Vec::new(),
                 },
                 SpriteAtlasDesc {
                     sprite: SpriteDesc {
@@ -415,6 +474,7 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Repeating,
                     ms: K_ANIMATION_FRAME_MS,
+                    events: Vec::new(),
                 },
                 SpriteAtlasDesc {
                     sprite: SpriteDesc {
@@ -427,6 +487,7 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Repeating,
                     ms: K_ANIMATION_FRAME_MS,
+                    events: Vec::new(),
                 },
                 SpriteAtlasDesc {
                     sprite: SpriteDesc {
@@ -439,6 +500,10 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Once,
                     ms: K_ANIMATION_FRAME_MS / 2,
+                    events: vec![FrameEvent {
+                        index: 3,
+                        event: AnimationEvent::Catch,
+                    }],
                 },
                 SpriteAtlasDesc {
                     sprite: SpriteDesc {
@@ -451,15 +516,19 @@ fn setup(
                     index: 0,
                     mode: TimerMode::Once,
                     ms: K_ANIMATION_FRAME_MS / 2,
+                    events: vec![FrameEvent {
+                        index: 4,
+                        event: AnimationEvent::Hit,
+                    }],
                 },
             ]),
             component: ObjectComponentType::Player,
-            position: Vec2::new(0.0, K_GROUND_LEVEL + 64.0),
+            position: Vec2::new(0.0, ground_level + 64.0),
             size: Vec2::new(128.0, 128.0),
             color: Color::srgb(1.0, 1.0, 1.0),
             name: "Player".to_string(),
         }],
-        t: LayerType::Player,
+        marker: PlayerLayer,
         depth: 5.0,
         speed: 0.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -473,12 +542,12 @@ fn setup(
                 ..default()
             }),
             component: ObjectComponentType::Boat,
-            position: Vec2::new(K_OCEAN_LAND_BORDER, K_GROUND_LEVEL + 16.0),
+            position: Vec2::new(K_OCEAN_LAND_BORDER, ground_level + 16.0),
             size: Vec2::new(160.0, 40.0),
             color: Color::srgb(1.0, 1.0, 1.0),
             name: "Boat".to_string(),
         }],
-        t: LayerType::Boat,
+        marker: BoatLayer,
         depth: 5.5,
         speed: 0.0,
         size: Vec2::new(K_WIDTH, K_HEIGHT),
@@ -556,3 +625,46 @@ fn setup(
         &mut materials,
     );
 }
+
+/// Layer object of a spawn zone over the `x` range of the ocean.
+fn spawn_zone_desc(
+    ground_level: f32,
+    name: &str,
+    x: Range<f32>,
+    weights: Vec<(FishType, f32)>,
+    rate: f32,
+    cap: usize,
+) -> LayerObjectDesc<ObjectComponentType> {
+    let zone = SpawnZone::new(weights, rate, cap, x.end - x.start);
+    let depth = (zone.depth.start + zone.depth.end) / 2.0;
+    LayerObjectDesc {
+        t: ObjectType::Primitive(PrimitiveType::Rectangle),
+        position: Vec2::new((x.start + x.end) / 2.0, ground_level - depth),
+        size: Vec2::new(x.end - x.start, zone.depth.end - zone.depth.start),
+        component: ObjectComponentType::SpawnZone(zone),
+        color: Color::srgba(1.0, 0.0, 0.0, 0.2),
+        name: name.to_string(),
+    }
+}
+
+/// Layer object of a protected area over the `x` range of the ocean, down to the hook depth.
+fn protected_area_desc(
+    ground_level: f32,
+    name: &str,
+    x: Range<f32>,
+    protection: Protection,
+    recovery: f32,
+) -> LayerObjectDesc<ObjectComponentType> {
+    let area = ProtectedArea::new(protection, recovery, x.end - x.start);
+    LayerObjectDesc {
+        t: ObjectType::Primitive(PrimitiveType::Rectangle),
+        position: Vec2::new(
+            (x.start + x.end) / 2.0,
+            ground_level - K_HOOK_MAX_DEPTH / 2.0,
+        ),
+        size: Vec2::new(x.end - x.start, K_HOOK_MAX_DEPTH),
+        component: ObjectComponentType::ProtectedArea(area),
+        color: Color::srgba(0.2, 0.9, 0.4, 0.15),
+        name: name.to_string(),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    ai::{Behaviour, Brain, Steering},
+    components::{
+        ActionRange, AnimationEvent, Boat, Building, Cloud, DayNightColor, Direction, Fish, Hunger,
+        Land, Npc, Ocean, OnAI, OnControl, Player, PlayerState, ProtectedArea, Sky, SpawnZone, Sun,
+        Swim, Velocity, ViewportFit,
+    },
+    constants::{K_BUOY_SIZE, K_FISH_CATCH_RANGE, K_SPEED},
+    layer::LayerComponent,
+};
+use bevy::prelude::*;
+
+/// Game Objects
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectComponentType {
+    Player,
+    Boat,
+    Land,
+    Ocean,
+    Building,
+    Sun,
+    Cloud(Cloud),
+    Sky,
+    Fish(Fish),
+    Npc(Npc),
+    SpawnZone(SpawnZone),
+    ProtectedArea(ProtectedArea),
+}
+
+impl LayerComponent for ObjectComponentType {
+    type Event = AnimationEvent;
+
+    fn insert(&self, entity: &mut EntityCommands, size: Vec2) {
+        match self {
+            ObjectComponentType::Player => {
+                entity
+                    .insert(Player::default())
+                    .insert(Direction::Right)
+                    .insert(PlayerState::Walk)
+                    .insert(Velocity { ..default() })
+                    .insert(OnControl);
+            }
+            ObjectComponentType::Boat => {
+                entity
+                    .insert(Boat)
+                    .insert(Velocity { ..default() })
+                    .insert(DayNightColor);
+            }
+            ObjectComponentType::Land => {
+                entity.insert(Land { size });
+            }
+            ObjectComponentType::Ocean => {
+                entity.insert(Ocean { size }).insert(ActionRange {
+                    range: size.x / 2.0,
+                });
+            }
+            ObjectComponentType::Building => {
+                entity
+                    .insert(Building)
+                    .insert(ActionRange {
+                        range: size.x / 2.0,
+                    })
+                    .insert(DayNightColor);
+            }
+            ObjectComponentType::Sun => {
+                entity.insert(Sun);
+            }
+            ObjectComponentType::Cloud(cloud) => {
+                entity
+                    .insert(cloud.clone())
+                    .insert(ViewportFit { size })
+                    .insert(DayNightColor);
+            }
+            ObjectComponentType::Sky => {
+                entity
+                    .insert(Sky)
+                    .insert(ViewportFit { size })
+                    .insert(DayNightColor);
+            }
+            ObjectComponentType::Fish(fish) => {
+                entity
+                    .insert(fish.clone())
+                    .insert(Direction::Left)
+                    .insert(Swim {
+                        velocity: Vec2::new(-K_SPEED, 0.0),
+                    })
+                    .insert(Steering {
+                        desired: Vec2::new(-K_SPEED, 0.0),
+                    })
+                    .insert(ActionRange {
+                        range: K_FISH_CATCH_RANGE,
+                    })
+                    .insert(OnAI);
+                if fish.t.is_predator() {
+                    // The patrol route depends on the ground level, it is added on spawn.
+                    entity.insert(Hunger::default()).insert(Brain::new(vec![
+                        Behaviour::Wander,
+                        Behaviour::Flee,
+                        Behaviour::Seek,
+                        Behaviour::Patrol,
+                        Behaviour::Rest,
+                    ]));
+                } else {
+                    entity.insert(Brain::new(vec![
+                        Behaviour::Wander,
+                        Behaviour::Flee,
+                        Behaviour::Seek,
+                        Behaviour::Rest,
+                    ]));
+                }
+            }
+            ObjectComponentType::Npc(npc) => {
+                entity.insert(npc.clone()).insert(ActionRange {
+                    range: size.x / 2.0,
+                });
+            }
+            ObjectComponentType::SpawnZone(zone) => {
+                // The zone area is only shown in the debug overlay.
+                entity.insert(zone.clone()).insert(Visibility::Hidden);
+            }
+            ObjectComponentType::ProtectedArea(area) => {
+                entity.insert(area.clone());
+                for x in [-size.x / 2.0, size.x / 2.0] {
+                    entity.with_child((
+                        Sprite::from_color(Color::srgb(1.0, 0.4, 0.0), K_BUOY_SIZE),
+                        Transform::from_xyz(x, (size.y + K_BUOY_SIZE.y) / 2.0, 0.5),
+                        Name::new("Buoy"),
+                    ));
+                }
+            }
+        }
+    }
+}
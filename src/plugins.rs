@@ -0,0 +1,349 @@
+use crate::{
+    achievements::AchievementsPlugin,
+    ai::{AiPlugin, AiSet},
+    constants::K_POPULATION_SECS,
+    contracts::ContractsPlugin,
+    dialogue::DialoguePlugin,
+    resources::{
+        Calendar, Ecosystem, EndConditions, FishGrid, FishStats, GameOutcome, PendingLoad,
+        PopulationTimer, Progress, Records, Regulations, ViewportConfig, ZoneDebug,
+    },
+    states::GameState,
+    systems,
+};
+use bevy::prelude::*;
+
+/// Game Plugins
+
+/// Update stages shared by the in-game and in-action states, run in declaration order.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Timer,
+    Spawn,
+    Input,
+    Movement,
+    Reaction,
+    AnimationControl,
+    Animation,
+    Render,
+}
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .configure_sets(
+                Update,
+                (
+                    GameSet::Timer,
+                    GameSet::Spawn,
+                    GameSet::Input,
+                    GameSet::Movement,
+                    GameSet::Reaction,
+                    GameSet::AnimationControl,
+                    GameSet::Animation,
+                    GameSet::Render,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame).or(in_state(GameState::InAction))),
+            )
+            .add_plugins((
+                WorldPlugin,
+                CameraPlugin,
+                PlayerPlugin,
+                FaunaPlugin,
+                SkyPlugin,
+                ConservationPlugin,
+                UiPlugin,
+                SessionPlugin,
+                RecordsPlugin,
+                AchievementsPlugin,
+                ContractsPlugin,
+                DialoguePlugin,
+            ));
+    }
+}
+
+/// Calendar, layers, viewport and generic sprite animation.
+pub struct WorldPlugin;
+
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewportConfig>()
+            .add_systems(PreStartup, systems::init_viewport)
+            // Resizes are handled in every state.
+            .add_systems(
+                Update,
+                (systems::window_resized, systems::layout_viewport).chain(),
+            )
+            .init_resource::<Calendar>()
+            .add_systems(Update, systems::calendar.in_set(GameSet::Timer))
+            .add_systems(Update, systems::added_animation.in_set(GameSet::Spawn))
+            .add_systems(
+                Update,
+                (systems::move_layer, systems::wrap_tiled)
+                    .chain()
+                    .in_set(GameSet::Movement)
+                    .after(systems::move_cloud),
+            )
+            .add_systems(
+                Update,
+                (systems::changed_active_sprite, systems::changed_direction)
+                    .chain()
+                    .in_set(GameSet::Reaction),
+            )
+            .add_systems(Update, systems::animation.in_set(GameSet::Animation));
+    }
+}
+
+/// Camera following the player.
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            systems::camera_follow
+                .in_set(GameSet::Movement)
+                .after(systems::move_control)
+                .before(systems::move_layer),
+        );
+    }
+}
+
+/// Player input, movement, state and actions.
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(systems::on_action)
+            .add_observer(systems::on_end_action)
+            .add_observer(systems::on_hook)
+            .add_observer(systems::on_sell)
+            .add_observer(systems::on_catch)
+            .add_observer(systems::on_hit)
+            .add_observer(systems::on_inspection)
+            .add_observer(systems::on_release)
+            .init_resource::<Regulations>()
+            .add_systems(
+                Update,
+                (
+                    systems::game_input.run_if(in_state(GameState::InGame)),
+                    systems::action_input.run_if(in_state(GameState::InAction)),
+                    systems::inspection,
+                )
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::player_state_walk_or_row,
+                    systems::move_control,
+                    systems::move_hook,
+                )
+                    .chain()
+                    .in_set(GameSet::Movement),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::changed_animation_player
+                        .run_if(in_state(GameState::InAction))
+                        .before(systems::changed_active_sprite),
+                    systems::changed_player_state.after(systems::changed_direction),
+                )
+                    .in_set(GameSet::Reaction),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::game_animation_control.run_if(in_state(GameState::InGame)),
+                    systems::action_animation_control.run_if(in_state(GameState::InAction)),
+                )
+                    .in_set(GameSet::AnimationControl),
+            );
+    }
+}
+
+/// Fish AI, movement and population.
+pub struct FaunaPlugin;
+
+impl Plugin for FaunaPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PopulationTimer {
+            timer: Timer::from_seconds(K_POPULATION_SECS, TimerMode::Repeating),
+        })
+        .add_systems(Update, systems::population_timer.in_set(GameSet::Timer))
+        .add_systems(
+            Update,
+            (
+                systems::fish_stats,
+                systems::fish_spawn,
+                systems::fish_hunger,
+            )
+                .chain()
+                .in_set(GameSet::Spawn),
+        )
+        .init_resource::<FishGrid>()
+        .init_resource::<FishStats>()
+        .init_resource::<ZoneDebug>()
+        // The zone overlay is toggled in every state.
+        .add_systems(
+            Update,
+            (systems::zone_debug_input, systems::zone_debug).chain(),
+        )
+        .add_plugins(AiPlugin)
+        .configure_sets(
+            Update,
+            (AiSet::Sense, AiSet::Think, AiSet::Act).in_set(GameSet::Input),
+        )
+        .add_systems(
+            Update,
+            systems::fish_grid
+                .in_set(GameSet::Input)
+                .before(AiSet::Sense),
+        )
+        .add_systems(
+            Update,
+            (
+                systems::ai_lure,
+                systems::ai_spook,
+                systems::ai_flee,
+                systems::ai_hunt,
+            )
+                .in_set(AiSet::Sense),
+        )
+        .add_systems(
+            Update,
+            (
+                systems::ai_input,
+                systems::ai_school,
+                systems::ai_bounds,
+                systems::fish_eat,
+            )
+                .chain()
+                .in_set(GameSet::Input)
+                .after(AiSet::Act),
+        )
+        .add_systems(Update, systems::move_ai.in_set(GameSet::Movement))
+        .add_systems(
+            Update,
+            systems::animation_ai.in_set(GameSet::AnimationControl),
+        );
+    }
+}
+
+/// Reputation, ecosystem health and the content they unlock.
+pub struct ConservationPlugin;
+
+impl Plugin for ConservationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Ecosystem>()
+            .init_resource::<Progress>()
+            .add_observer(systems::on_story)
+            .add_systems(
+                Update,
+                systems::ecosystem_health
+                    .in_set(GameSet::Spawn)
+                    .after(systems::fish_stats),
+            )
+            .add_systems(
+                Update,
+                systems::open_sea_limit
+                    .in_set(GameSet::Movement)
+                    .after(systems::move_control)
+                    .before(systems::move_hook)
+                    .before(systems::camera_follow),
+            )
+            .add_systems(Update, systems::milestones.in_set(GameSet::Reaction));
+    }
+}
+
+/// Records book, trophies and the hut wall.
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Records>()
+            .add_observer(systems::on_landed)
+            .add_systems(Update, systems::trophy_wall.in_set(GameSet::Spawn));
+    }
+}
+
+/// End of the run, summary screen, restart and save/load.
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EndConditions>()
+            .init_resource::<GameOutcome>()
+            .init_resource::<PendingLoad>()
+            .add_systems(
+                Update,
+                systems::end_conditions
+                    .in_set(GameSet::Reaction)
+                    .after(systems::milestones),
+            )
+            .add_systems(
+                Update,
+                systems::save_input
+                    .run_if(in_state(GameState::InGame))
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(
+                Update,
+                systems::summary_input.run_if(in_state(GameState::InSummary)),
+            )
+            .add_systems(OnEnter(GameState::InSummary), systems::enter_summary)
+            .add_systems(OnExit(GameState::InSummary), systems::exit_summary)
+            // The level itself is set up again by the game, between these.
+            .add_systems(
+                OnEnter(GameState::Restart),
+                (systems::restart, systems::spawn_hud, systems::apply_load).chain(),
+            );
+    }
+}
+
+/// Sun, clouds and day-night lighting.
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (systems::move_sun, systems::move_cloud).in_set(GameSet::Movement),
+        )
+        .add_systems(Update, systems::color_day_night.in_set(GameSet::Render));
+    }
+}
+
+/// HUD, player menu, journal and statistics screen.
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (systems::menu_input, systems::changed_player_menu)
+                .chain()
+                .run_if(in_state(GameState::InPlayerMenu)),
+        )
+        .add_systems(OnEnter(GameState::InPlayerMenu), systems::enter_player_menu)
+        .add_systems(OnExit(GameState::InPlayerMenu), systems::exit_player_menu)
+        .add_systems(
+            Update,
+            systems::stats_input.run_if(in_state(GameState::InStats)),
+        )
+        .add_systems(Startup, systems::spawn_hud)
+        .add_systems(Update, systems::update_hud)
+        .add_systems(OnEnter(GameState::InStats), systems::enter_stats)
+        .add_systems(OnExit(GameState::InStats), systems::exit_stats)
+        .add_systems(
+            Update,
+            systems::journal_input.run_if(in_state(GameState::InJournal)),
+        )
+        .add_systems(OnEnter(GameState::InJournal), systems::enter_journal)
+        .add_systems(OnExit(GameState::InJournal), systems::exit_journal);
+    }
+}
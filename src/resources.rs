@@ -1,6 +1,740 @@
+use std::collections::{HashMap, HashSet};
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{AnimationConfig, Player},
+    constants::{
+        K_GROUND_HEIGHT, K_HEIGHT, K_NIGHT_END, K_NIGHT_START, K_SCHOOL_RADIUS, K_SECS_IN_DAY,
+        K_WIDTH,
+    },
+    items::{self, FishType, Season},
+    save::SaveGame,
+};
+
+/// Fishing rules checked by inspections, limits are per species.
+#[derive(Resource, Debug, Clone)]
+pub struct Regulations {
+    // Fish of a species that may be kept per day of catch
+    pub bag_limits: HashMap<FishType, usize>,
+    // Minimum legal weight in kg
+    pub min_weights: HashMap<FishType, f32>,
+    pub closed_seasons: HashMap<FishType, Season>,
+    // Fine per illegal fish
+    pub fine: f32,
+}
+
+impl Default for Regulations {
+    fn default() -> Self {
+        Regulations {
+            bag_limits: HashMap::from([
+                (FishType::Fish, 20),
+                (FishType::Ray, 5),
+                (FishType::Shark, 1),
+            ]),
+            min_weights: HashMap::from([
+                (FishType::Fish, 0.5),
+                (FishType::Ray, 2.0),
+                (FishType::Shark, 15.0),
+            ]),
+            closed_seasons: HashMap::from([
+                (FishType::Ray, Season::Spring),
+                (FishType::Shark, Season::Summer),
+            ]),
+            fine: 10.0,
+        }
+    }
+}
+
+impl Regulations {
+    /// Why the fish would be illegal to keep, ignoring the bag limit.
+    pub fn violation(&self, fish: &items::Fish) -> Option<String> {
+        if let Some(min) = self.min_weights.get(&fish.t)
+            && fish.weight < *min
+        {
+            return Some(format!("undersized {:.2} < {:.2} kg", fish.weight, min));
+        }
+        if let Some(season) = self.closed_seasons.get(&fish.t)
+            && Season::from_day(fish.day) == *season
+        {
+            return Some(format!("caught in closed season {:?}", season));
+        }
+        None
+    }
+
+    /// Inventory indices of the illegal fish with the reason. The bags count the fish kept on
+    /// their day of catch, so fish sold since still fill them.
+    pub fn check(&self, player: &Player, records: &Records, day: u32) -> Vec<(usize, String)> {
+        let licensed = player.has_license(day);
+        let mut held: HashMap<(FishType, u32), u32> = HashMap::new();
+        for item in &player.items {
+            if let items::Item::Fish(fish) = item {
+                *held.entry((fish.t, fish.day)).or_default() += 1;
+            }
+        }
+        let mut bags: HashMap<(FishType, u32), u32> = HashMap::new();
+        let mut illegal = Vec::new();
+        for (index, item) in player.items.iter().enumerate() {
+            let items::Item::Fish(fish) = item else {
+                continue;
+            };
+            let key = (fish.t, fish.day);
+            let bag = bags
+                .entry(key)
+                .or_insert_with(|| records.bagged(fish.t, fish.day).saturating_sub(held[&key]));
+            *bag += 1;
+            let reason = if !licensed {
+                Some(String::from("no license"))
+            } else if self
+                .bag_limits
+                .get(&fish.t)
+                .is_some_and(|limit| *bag as usize > *limit)
+            {
+                Some(String::from("over the bag limit"))
+            } else {
+                self.violation(fish)
+            };
+            if let Some(reason) = reason {
+                illegal.push((index, reason));
+            }
+        }
+        illegal
+    }
+}
+
+/// Sprite, animation and size of a species, used to spawn fresh fish.
+#[derive(Debug, Clone)]
+pub struct SpeciesDef {
+    pub t: FishType,
+    pub sprite: Sprite,
+    pub animation: AnimationConfig,
+    pub size: Vec2,
+}
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct FishSpecies {
+    pub species: Vec<SpeciesDef>,
+}
+
+impl FishSpecies {
+    pub fn get(&self, t: FishType) -> Option<&SpeciesDef> {
+        self.species.iter().find(|def| def.t == t)
+    }
+}
+
+/// Live count of every species and its history over in-game days.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct FishStats {
+    pub counts: HashMap<FishType, usize>,
+    // Counts at the start of the recorded days, oldest first
+    pub history: Vec<HashMap<FishType, usize>>,
+    // Next in-game day to record
+    pub day: u32,
+    // Whether the spawn zones were filled for this run
+    pub seeded: bool,
+}
+
+impl FishStats {
+    pub fn count(&self, t: FishType) -> usize {
+        self.counts.get(&t).copied().unwrap_or(0)
+    }
+}
+
+/// Ecosystem health in [0, 1] from species diversity and population relative to the caps.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct Ecosystem {
+    pub health: f32,
+    // Evenness of the species populations in [0, 1]
+    pub diversity: f32,
+    // Mean population relative to the caps in [0, 1]
+    pub abundance: f32,
+}
+
+impl Ecosystem {
+    pub fn from_stats(stats: &FishStats) -> Self {
+        let ratios: Vec<f32> = FishType::ALL
+            .iter()
+            .map(|t| (stats.count(*t) as f32 / t.max_population() as f32).min(1.0))
+            .collect();
+        let total: f32 = ratios.iter().sum();
+        let abundance = total / ratios.len() as f32;
+        // Shannon evenness of the relative populations.
+        let diversity = if total > 0.0 {
+            let entropy: f32 = ratios
+                .iter()
+                .filter(|ratio| **ratio > 0.0)
+                .map(|ratio| {
+                    let p = ratio / total;
+                    -p * p.ln()
+                })
+                .sum();
+            entropy / (ratios.len() as f32).ln()
+        } else {
+            0.0
+        };
+        Ecosystem {
+            health: (diversity + abundance) / 2.0,
+            diversity,
+            abundance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Milestone {
+    OpenSea,
+    TrustedFisher,
+    EcosystemDecline,
+    EcosystemThriving,
+}
+
+impl Milestone {
+    pub fn text(&self) -> &'static str {
+        match self {
+            Milestone::OpenSea => "The harbour master lets you row out to the open sea.",
+            Milestone::TrustedFisher => "The hut owner trusts you and gives you a discount.",
+            Milestone::EcosystemDecline => "The old fishermen worry: the fish are disappearing.",
+            Milestone::EcosystemThriving => "The sea is thriving again, thanks to you.",
+        }
+    }
+}
+
+/// Milestones reached so far and the last story message.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Progress {
+    pub reached: HashSet<Milestone>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trophy {
+    FirstCatch,
+    FirstRay,
+    FirstShark,
+    TenFish,
+    HundredFish,
+}
+
+impl Trophy {
+    pub fn text(&self) -> &'static str {
+        match self {
+            Trophy::FirstCatch => "First catch",
+            Trophy::FirstRay => "First ray",
+            Trophy::FirstShark => "First shark",
+            Trophy::TenFish => "10 fish",
+            Trophy::HundredFish => "100 fish",
+        }
+    }
+}
+
+/// Records book: heaviest fish per species, trophies and what is mounted on the hut wall.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Records {
+    pub best: HashMap<FishType, items::Fish>,
+    // Fish landed in the run, released ones included
+    pub caught: u32,
+    pub trophies: HashSet<Trophy>,
+    pub mounted: HashSet<FishType>,
+    // Per species counts for the journal
+    #[serde(default)]
+    pub landed: HashMap<FishType, u32>,
+    #[serde(default)]
+    pub released: HashMap<FishType, u32>,
+    // Fish kept per species and day of catch, for the bag limits
+    #[serde(default)]
+    pub bags: HashMap<(FishType, u32), u32>,
+}
+
+impl Records {
+    /// Counts the fish and returns whether it is a new record.
+    pub fn record(&mut self, fish: &items::Fish) -> bool {
+        self.caught += 1;
+        *self.landed.entry(fish.t).or_default() += 1;
+        let best = self
+            .best
+            .get(&fish.t)
+            .is_none_or(|best| best.weight < fish.weight);
+        if best {
+            self.best.insert(fish.t, fish.clone());
+        }
+        best
+    }
+
+    /// Puts the fish in the bag of its species and day of catch.
+    pub fn bag(&mut self, fish: &items::Fish) {
+        *self.bags.entry((fish.t, fish.day)).or_default() += 1;
+    }
+
+    /// Counts the release, the fish leaves its bag.
+    pub fn release(&mut self, fish: &items::Fish) {
+        *self.released.entry(fish.t).or_default() += 1;
+        if let Some(bag) = self.bags.get_mut(&(fish.t, fish.day)) {
+            *bag = bag.saturating_sub(1);
+        }
+    }
+
+    pub fn bagged(&self, t: FishType, day: u32) -> u32 {
+        self.bags.get(&(t, day)).copied().unwrap_or(0)
+    }
 
+    pub fn landed(&self, t: FishType) -> u32 {
+        self.landed.get(&t).copied().unwrap_or(0)
+    }
+
+    pub fn released(&self, t: FishType) -> u32 {
+        self.released.get(&t).copied().unwrap_or(0)
+    }
+
+    /// Trophies earned so far, won or not.
+    pub fn earned(&self) -> Vec<Trophy> {
+        let mut earned = Vec::new();
+        if self.caught >= 1 {
+            earned.push(Trophy::FirstCatch);
+        }
+        if self.best.contains_key(&FishType::Ray) {
+            earned.push(Trophy::FirstRay);
+        }
+        if self.best.contains_key(&FishType::Shark) {
+            earned.push(Trophy::FirstShark);
+        }
+        if self.caught >= 10 {
+            earned.push(Trophy::TenFish);
+        }
+        if self.caught >= 100 {
+            earned.push(Trophy::HundredFish);
+        }
+        earned
+    }
+}
+
+/// Shows the spawn zones.
+#[derive(Resource, Default, Debug)]
+pub struct ZoneDebug {
+    pub enabled: bool,
+}
+
+/// In-game time of the current run.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct Calendar {
+    pub secs: f32,
+}
+
+impl Calendar {
+    /// Current in-game day, starting at 0.
+    pub fn day(&self) -> u32 {
+        (self.secs / K_SECS_IN_DAY) as u32
+    }
+
+    /// Hour of the current day in [0, 24).
+    pub fn hour(&self) -> f32 {
+        (24.0 * self.secs / K_SECS_IN_DAY) % 24.0
+    }
+
+    pub fn is_night(&self) -> bool {
+        let hour = self.hour();
+        !(K_NIGHT_END..K_NIGHT_START).contains(&hour)
+    }
+}
+
+/// Cadence of population checks such as fish spawning, separate from AI decisions.
 #[derive(Resource, Debug, Clone, PartialEq)]
-pub struct AITimer {
+pub struct PopulationTimer {
     pub timer: Timer,
 }
+
+/// Window size and the visible world area derived from it.
+///
+/// The camera always shows at least the virtual `K_WIDTH` x `K_HEIGHT` area; extra window space
+/// extends the visible area along one axis.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ViewportConfig {
+    // Window size in logical pixels
+    pub window: Vec2,
+    // Visible world size
+    pub size: Vec2,
+    // World units per logical pixel
+    pub scale: f32,
+    // World height of the ground and water surface
+    pub ground_level: f32,
+}
+
+impl ViewportConfig {
+    pub fn from_window(window: Vec2) -> Self {
+        let scale = f32::max(K_WIDTH / window.x, K_HEIGHT / window.y);
+        ViewportConfig {
+            window,
+            size: window * scale,
+            scale,
+            ground_level: K_GROUND_HEIGHT - window.y * scale / 2.0,
+        }
+    }
+
+    /// Rescales the view to the window, the ground stays where the world was laid out.
+    pub fn resize(&mut self, window: Vec2) {
+        *self = ViewportConfig {
+            ground_level: self.ground_level,
+            ..Self::from_window(window)
+        };
+    }
+}
+
+impl Default for ViewportConfig {
+    fn default() -> Self {
+        Self::from_window(Vec2::new(K_WIDTH, K_HEIGHT))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub t: FishType,
+}
+
+/// Uniform grid of fish rebuilt every frame for neighbour lookups.
+#[derive(Resource, Debug, Default)]
+pub struct FishGrid {
+    cells: HashMap<IVec2, Vec<GridEntry>>,
+}
+
+impl FishGrid {
+    fn cell(position: Vec2) -> IVec2 {
+        (position / K_SCHOOL_RADIUS).floor().as_ivec2()
+    }
+
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entry: GridEntry) {
+        self.cells
+            .entry(Self::cell(entry.position))
+            .or_default()
+            .push(entry);
+    }
+
+    /// Entries in the cells overlapping the `radius` around `position`, callers filter by
+    /// distance.
+    pub fn neighbours(&self, position: Vec2, radius: f32) -> impl Iterator<Item = &GridEntry> {
+        let cell = Self::cell(position);
+        let span = (radius / K_SCHOOL_RADIUS).ceil() as i32;
+        (-span..=span)
+            .flat_map(move |y| (-span..=span).map(move |x| cell + IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+}
+
+/// Thresholds that end the run.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct EndConditions {
+    // The ecosystem collapses below these
+    pub min_fish: usize,
+    pub min_species: usize,
+    // Victory needs the money goal with a healthy ecosystem by the last day
+    pub money_goal: f32,
+    pub min_health: f32,
+    pub last_day: u32,
+}
+
+impl Default for EndConditions {
+    fn default() -> Self {
+        EndConditions {
+            min_fish: 20,
+            min_species: 2,
+            money_goal: 500.0,
+            min_health: 0.6,
+            last_day: 60,
+        }
+    }
+}
+
+impl EndConditions {
+    /// Whether the population is judged on the day, the spawn zones get the first day to fill
+    /// the sea.
+    pub fn settled(day: u32) -> bool {
+        day >= 1
+    }
+
+    /// How the run ends on the day, if it does: a collapse comes first, then a victory, then
+    /// the end of the season.
+    pub fn outcome(
+        &self,
+        day: u32,
+        total: usize,
+        species: usize,
+        money: f32,
+        health: f32,
+    ) -> Option<Outcome> {
+        if EndConditions::settled(day) && (total < self.min_fish || species < self.min_species) {
+            Some(Outcome::Collapse)
+        } else if money >= self.money_goal && health >= self.min_health {
+            Some(Outcome::Victory)
+        } else if day > self.last_day {
+            Some(Outcome::Timeout)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Victory,
+    Collapse,
+    Timeout,
+}
+
+impl Outcome {
+    pub fn text(&self) -> &'static str {
+        match self {
+            Outcome::Victory => "Victory! You made a living and the sea is healthy.",
+            Outcome::Collapse => "Game over: the ecosystem collapsed.",
+            Outcome::Timeout => "Game over: the season is over.",
+        }
+    }
+}
+
+/// How the current run ended, if it did.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct GameOutcome {
+    pub outcome: Option<Outcome>,
+}
+
+/// Save applied once the level is set up again.
+#[derive(Resource, Default, Debug)]
+pub struct PendingLoad {
+    pub save: Option<SaveGame>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::K_DAYS_IN_SEASON,
+        items::{Bait, BaitType, Item},
+    };
+
+    fn fish(t: FishType, weight: f32, day: u32) -> Item {
+        Item::Fish(items::Fish { t, weight, day })
+    }
+
+    fn reasons(illegal: &[(usize, String)]) -> Vec<(usize, &str)> {
+        illegal
+            .iter()
+            .map(|(index, reason)| (*index, reason.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn unlicensed_fish_are_illegal() {
+        let regulations = Regulations::default();
+        let player = Player {
+            items: vec![fish(FishType::Fish, 1.0, 14), fish(FishType::Fish, 1.0, 14)],
+            license: Some(13),
+            ..default()
+        };
+        let illegal = regulations.check(&player, &Records::default(), 14);
+        assert_eq!(reasons(&illegal), [(0, "no license"), (1, "no license")]);
+    }
+
+    #[test]
+    fn legal_catch_passes() {
+        let regulations = Regulations::default();
+        let player = Player {
+            items: vec![
+                fish(FishType::Fish, 1.0, 14),
+                fish(FishType::Ray, 3.0, 14),
+                fish(FishType::Shark, 20.0, 14),
+            ],
+            license: Some(14),
+            ..default()
+        };
+        assert!(
+            regulations
+                .check(&player, &Records::default(), 14)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn bag_limits_count_per_day_of_catch() {
+        let regulations = Regulations::default();
+        let player = Player {
+            items: vec![
+                fish(FishType::Shark, 20.0, 14),
+                fish(FishType::Shark, 20.0, 15),
+                fish(FishType::Shark, 20.0, 15),
+            ],
+            license: Some(20),
+            ..default()
+        };
+        let illegal = regulations.check(&player, &Records::default(), 15);
+        assert_eq!(reasons(&illegal), [(2, "over the bag limit")]);
+    }
+
+    #[test]
+    fn sold_fish_still_fill_the_bag() {
+        let regulations = Regulations::default();
+        let shark = items::Fish {
+            t: FishType::Shark,
+            weight: 20.0,
+            day: 15,
+        };
+        let mut records = Records::default();
+        // The first shark of the day was sold, the second is still held.
+        records.bag(&shark);
+        records.bag(&shark);
+        let player = Player {
+            items: vec![Item::Fish(shark.clone())],
+            license: Some(20),
+            ..default()
+        };
+        let illegal = regulations.check(&player, &records, 15);
+        assert_eq!(reasons(&illegal), [(0, "over the bag limit")]);
+
+        // Released fish leave the bag.
+        records.release(&shark);
+        assert!(regulations.check(&player, &records, 15).is_empty());
+        assert_eq!(records.released(FishType::Shark), 1);
+    }
+
+    #[test]
+    fn undersized_and_closed_season_fish_are_illegal() {
+        let regulations = Regulations::default();
+        let player = Player {
+            items: vec![
+                fish(FishType::Fish, 0.2, 14),
+                Item::Bait(Bait {
+                    t: BaitType::Worm,
+                    count: 3,
+                }),
+                fish(FishType::Ray, 3.0, 0),
+                fish(FishType::Ray, 3.0, K_DAYS_IN_SEASON),
+            ],
+            license: Some(20),
+            ..default()
+        };
+        let illegal = regulations.check(&player, &Records::default(), 14);
+        assert_eq!(illegal.len(), 2);
+        assert_eq!(illegal[0].0, 0);
+        assert!(illegal[0].1.starts_with("undersized"));
+        assert_eq!(illegal[1].0, 2);
+        assert!(illegal[1].1.starts_with("caught in closed season"));
+    }
+
+    fn stats(counts: &[(FishType, usize)]) -> FishStats {
+        FishStats {
+            counts: counts.iter().copied().collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn empty_ecosystem_has_no_health() {
+        let ecosystem = Ecosystem::from_stats(&stats(&[]));
+        assert_eq!(ecosystem.health, 0.0);
+        assert_eq!(ecosystem.diversity, 0.0);
+        assert_eq!(ecosystem.abundance, 0.0);
+    }
+
+    #[test]
+    fn full_even_ecosystem_is_healthy() {
+        let counts: Vec<(FishType, usize)> = FishType::ALL
+            .iter()
+            .map(|t| (*t, t.max_population()))
+            .collect();
+        let ecosystem = Ecosystem::from_stats(&stats(&counts));
+        assert!((ecosystem.diversity - 1.0).abs() < 1e-5);
+        assert!((ecosystem.abundance - 1.0).abs() < 1e-5);
+        assert!((ecosystem.health - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn single_species_ecosystem_lacks_diversity() {
+        let t = FishType::Fish;
+        // Populations over the cap count as the cap.
+        let ecosystem = Ecosystem::from_stats(&stats(&[(t, 2 * t.max_population())]));
+        assert_eq!(ecosystem.diversity, 0.0);
+        assert!((ecosystem.abundance - 1.0 / FishType::ALL.len() as f32).abs() < 1e-5);
+        assert!((ecosystem.health - ecosystem.abundance / 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn run_goes_on_without_an_end_condition() {
+        let conditions = EndConditions::default();
+        assert_eq!(conditions.outcome(5, 100, 3, 100.0, 0.8), None);
+        // The sea is still filling up on the first day.
+        assert_eq!(conditions.outcome(0, 0, 0, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn collapse_comes_before_victory_and_timeout() {
+        let conditions = EndConditions::default();
+        let late = conditions.last_day + 1;
+        assert_eq!(
+            conditions.outcome(late, 5, 3, 1000.0, 0.8),
+            Some(Outcome::Collapse)
+        );
+        assert_eq!(
+            conditions.outcome(late, 100, 1, 1000.0, 0.8),
+            Some(Outcome::Collapse)
+        );
+    }
+
+    #[test]
+    fn victory_needs_money_and_health_before_timeout() {
+        let conditions = EndConditions::default();
+        let late = conditions.last_day + 1;
+        assert_eq!(
+            conditions.outcome(late, 100, 3, 500.0, 0.6),
+            Some(Outcome::Victory)
+        );
+        assert_eq!(
+            conditions.outcome(late, 100, 3, 500.0, 0.5),
+            Some(Outcome::Timeout)
+        );
+        assert_eq!(
+            conditions.outcome(conditions.last_day, 100, 3, 499.0, 0.8),
+            None
+        );
+    }
+
+    fn landed(t: FishType, weight: f32) -> items::Fish {
+        items::Fish { t, weight, day: 1 }
+    }
+
+    #[test]
+    fn records_keep_the_heaviest_fish() {
+        let mut records = Records::default();
+        assert!(records.record(&landed(FishType::Ray, 3.0)));
+        assert!(!records.record(&landed(FishType::Ray, 2.0)));
+        assert!(!records.record(&landed(FishType::Ray, 3.0)));
+        assert!(records.record(&landed(FishType::Ray, 4.5)));
+        assert!(records.record(&landed(FishType::Fish, 0.5)));
+        assert_eq!(records.best[&FishType::Ray].weight, 4.5);
+        assert_eq!(records.caught, 5);
+        assert_eq!(records.landed(FishType::Ray), 4);
+        assert_eq!(records.landed(FishType::Shark), 0);
+    }
+
+    #[test]
+    fn trophies_are_earned_by_species_and_count() {
+        let mut records = Records::default();
+        assert!(records.earned().is_empty());
+        records.record(&landed(FishType::Fish, 1.0));
+        assert_eq!(records.earned(), [Trophy::FirstCatch]);
+        records.record(&landed(FishType::Shark, 30.0));
+        for _ in 0..8 {
+            records.record(&landed(FishType::Fish, 1.0));
+        }
+        assert_eq!(
+            records.earned(),
+            [Trophy::FirstCatch, Trophy::FirstShark, Trophy::TenFish]
+        );
+    }
+}
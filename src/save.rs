@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::Player,
+    constants::K_SAVE_PATH,
+    contracts::Contracts,
+    items::{self, BaitType, FishType},
+    resources::{Milestone, Records},
+};
+
+/// Save Game
+///
+/// Snapshot of a run written as RON, applied on top of a freshly set up level.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub money: f32,
+    pub items: Vec<items::Item>,
+    pub bait: Option<BaitType>,
+    pub license: Option<u32>,
+    pub reputation: f32,
+}
+
+impl SavedPlayer {
+    pub fn from_player(player: &Player) -> Self {
+        SavedPlayer {
+            money: player.money,
+            items: player.items.clone(),
+            bait: player.bait,
+            license: player.license,
+            reputation: player.reputation,
+        }
+    }
+
+    pub fn apply(&self, player: &mut Player) {
+        player.money = self.money;
+        player.items = self.items.clone();
+        player.bait = self.bait;
+        player.license = self.license;
+        player.reputation = self.reputation;
+    }
+}
+
+/// Fish alive in the fauna layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedFish {
+    pub t: FishType,
+    pub x: f32,
+    // Below the ground level, which depends on the window, older saves start at the surface
+    #[serde(default)]
+    pub depth: f32,
+    pub right: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    // In-game time of the run
+    pub secs: f32,
+    pub player: SavedPlayer,
+    pub fish: Vec<SavedFish>,
+    pub history: Vec<HashMap<FishType, usize>>,
+    pub stats_day: u32,
+    pub milestones: Vec<Milestone>,
+    // Older saves start without records and contracts
+    #[serde(default)]
+    pub records: Records,
+    #[serde(default)]
+    pub contracts: Contracts,
+}
+
+impl SaveGame {
+    pub fn write(&self) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())?;
+        fs::write(K_SAVE_PATH, text).map_err(|error| error.to_string())
+    }
+
+    pub fn read() -> Result<Self, String> {
+        let text = fs::read_to_string(K_SAVE_PATH).map_err(|error| error.to_string())?;
+        ron::from_str(&text).map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::{Contract, ContractDef, Task};
+
+    fn save() -> SaveGame {
+        let ray = items::Fish {
+            t: FishType::Ray,
+            weight: 3.5,
+            day: 4,
+        };
+        let mut records = Records::default();
+        records.record(&ray);
+        records.bag(&ray);
+        SaveGame {
+            secs: 150.0,
+            player: SavedPlayer {
+                money: 42.5,
+                items: vec![
+                    items::Item::Fish(ray),
+                    items::Item::Bait(items::Bait {
+                        t: BaitType::Shrimp,
+                        count: 3,
+                    }),
+                ],
+                bait: Some(BaitType::Worm),
+                license: Some(9),
+                reputation: -1.5,
+            },
+            fish: vec![SavedFish {
+                t: FishType::Shark,
+                x: 1200.0,
+                depth: 180.0,
+                right: true,
+            }],
+            history: vec![HashMap::from([(FishType::Fish, 40), (FishType::Ray, 8)])],
+            stats_day: 5,
+            milestones: vec![Milestone::OpenSea],
+            records,
+            contracts: Contracts {
+                active: vec![Contract {
+                    def: ContractDef {
+                        name: String::from("Ray delivery"),
+                        task: Task::Deliver {
+                            t: FishType::Ray,
+                            count: 3,
+                        },
+                        reward: 60.0,
+                        days: 5,
+                    },
+                    progress: 1,
+                    deadline: 8,
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn save_round_trip() {
+        let save = save();
+        let text = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()).unwrap();
+        let loaded: SaveGame = ron::from_str(&text).unwrap();
+        assert_eq!(loaded, save);
+    }
+
+    #[test]
+    fn old_save_without_records_and_contracts() {
+        let text = r#"(
+            secs: 45.0,
+            player: (
+                money: 12.5,
+                items: [Fish((t: Fish, weight: 1.2, day: 1))],
+                bait: None,
+                license: None,
+                reputation: 0.0,
+            ),
+            fish: [(t: Fish, x: 700.0, y: -300.0, right: false)],
+            history: [{Fish: 30}],
+            stats_day: 2,
+            milestones: [],
+        )"#;
+        let loaded: SaveGame = ron::from_str(text).unwrap();
+        assert_eq!(loaded.player.money, 12.5);
+        assert_eq!(loaded.fish.len(), 1);
+        assert_eq!(loaded.fish[0].depth, 0.0);
+        assert_eq!(loaded.records, Records::default());
+        assert_eq!(loaded.contracts, Contracts::default());
+    }
+}
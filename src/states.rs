@@ -1,9 +1,16 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States, Serialize, Deserialize)]
 pub enum GameState {
     #[default]
     InGame,
     InAction,
     InPlayerMenu,
+    InStats,
+    InJournal,
+    InDialogue,
+    InSummary,
+    // Level teardown and setup, then back in game
+    Restart,
 }
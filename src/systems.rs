@@ -1,63 +1,227 @@
+use std::collections::HashSet;
+use std::f32::consts::{PI, TAU};
 use std::time::Duration;
 
 use crate::{
+    ai::{Behaviour, Brain, DecisionTimer, Patrol, Senses, Steering, Stimulus},
     components::{
-        ActionRange, ActiveSprite, AnimationConfig, AnimationState, AnimationTimer, Boat, Building,
-        Cloud, DayNightColor, DefaultColor, Direction, Fish, Layer, OnAI, OnControl, Player,
-        PlayerMenu, PlayerState, SpriteCollection, Sun, Velocity,
+        ActionRange, AnimationConfig, AnimationEvent, AnimationState, Boat, Building,
+        CameraController, Cloud, DayNightColor, Direction, FaunaLayer, Fish, FishingHook,
+        FishingLine, Hud, Hunger, JournalScreen, MountedTrophy, Npc, OnAI, OnControl, Player,
+        PlayerMenu, PlayerState, ProtectedArea, Protection, SpawnZone, SpriteCollection,
+        StatsScreen, SummaryScreen, Sun, Swim, Velocity, ViewportFit,
     },
     constants::{
-        K_FISH_AREA_BORDER, K_FISH_AREA_SIZE, K_FISH_MAX_POPULATION, K_GROUND_LEVEL, K_HEIGHT,
-        K_INVENTORY_SIZE, K_OCEAN_LAND_BORDER, K_SECS_IN_DAY, K_SIT_OFFSET, K_SPEED, K_WIDTH,
+        K_BAIT_PACK, K_BAIT_PER_FISH, K_BITE_CHANCE, K_CHART_SIZE, K_DECLINE_HEALTH,
+        K_DISCOUNT_REPUTATION, K_EAT_RANGE, K_FISH_AREA_BORDER, K_FISH_AREA_SIZE, K_FISH_MAX_TILT,
+        K_FISH_TURN_RATE, K_FLEE_RADIUS, K_FLEE_SPEED, K_HEIGHT, K_HOOK_CAST_DEPTH,
+        K_HOOK_MAX_DEPTH, K_HOOK_SPEED, K_HOOK_STILL_SECS, K_HOOK_TAP_SECS, K_HUNGRY_SECS,
+        K_HUNT_RADIUS, K_HUNT_SPEED, K_INSPECTION_RATE, K_INVENTORY_SIZE, K_LICENSE_DAYS,
+        K_LICENSE_PRICE, K_LURE_RADIUS, K_LURE_SCORE, K_LURE_SPEED, K_OCEAN_LAND_BORDER,
+        K_OPEN_SEA_BORDER, K_OPEN_SEA_REPUTATION, K_OVERFISHED_RATIO, K_OVERFISHING_REPUTATION,
+        K_POPULATION_SECS, K_PROTECTED_REPUTATION, K_RELEASE_MORTALITY, K_RELEASE_REPUTATION,
+        K_ROD_OFFSET, K_SCHOOL_ALIGNMENT_WEIGHT, K_SCHOOL_COHESION_WEIGHT, K_SCHOOL_RADIUS,
+        K_SCHOOL_SEPARATION, K_SCHOOL_SEPARATION_WEIGHT, K_SECS_IN_DAY, K_SIT_OFFSET, K_SPEED,
+        K_SPOOK_RADIUS, K_STARVE_SECS, K_STATS_DAYS, K_SUSTAINABLE_REPUTATION, K_THRIVING_HEALTH,
+        K_TROPHY_SPACING, K_TROPHY_WALL, K_WIDTH, K_WORLD_LEFT, K_WORLD_RIGHT,
     },
-    events::{Action, Catch, EndAction, Hit, Hook, Sell},
-    items::{self, Value, Weight},
-    resources::AITimer,
+    contracts::{self, ContractBoard, Contracts},
+    dialogue::DialogueMarks,
+    events::{
+        Action, Catch, EndAction, Hit, Hook, Inspection, Landed, Release, Released, Sell, Sold,
+        Story, Talk,
+    },
+    items::{self, Season, Value, Weight},
+    layer::{ActiveSprite, AnimationTimer, DefaultColor, Layer, LayerComponent},
+    objects::ObjectComponentType,
+    resources::{
+        Calendar, Ecosystem, EndConditions, FishGrid, FishSpecies, FishStats, GameOutcome,
+        GridEntry, Milestone, PendingLoad, PopulationTimer, Progress, Records, Regulations,
+        SpeciesDef, ViewportConfig, ZoneDebug,
+    },
+    save::{SaveGame, SavedFish, SavedPlayer},
     states::GameState,
 };
 use bevy::app::AppExit;
 use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
 use rand::Rng;
 
 ///
 /// Resources
 ///
 
-pub fn ai_timer(time: Res<Time<Virtual>>, mut ai: ResMut<AITimer>) {
-    ai.timer.tick(time.delta());
+pub fn calendar(time: Res<Time<Virtual>>, mut calendar: ResMut<Calendar>) {
+    calendar.secs += time.delta_secs();
+}
+
+pub fn population_timer(time: Res<Time<Virtual>>, mut population: ResMut<PopulationTimer>) {
+    population.timer.tick(time.delta());
+}
+
+pub fn window_resized(
+    mut resize_events: MessageReader<WindowResized>,
+    mut viewport: ResMut<ViewportConfig>,
+) {
+    if let Some(event) = resize_events.read().last() {
+        info!("Window resized: {}x{}", event.width, event.height);
+        viewport.resize(Vec2::new(event.width, event.height));
+    }
+}
+
+/// Sets up the viewport for the primary window before the world is laid out.
+pub fn init_viewport(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut viewport: ResMut<ViewportConfig>,
+) {
+    *viewport = ViewportConfig::from_window(window.size());
+    info!(
+        "Viewport: {}x{}, ground at {}",
+        viewport.size.x, viewport.size.y, viewport.ground_level
+    );
 }
 
 ///
 /// Observers
 ///
 
-pub fn on_hook(_action: On<Hook>, player: Single<&mut PlayerState, With<Player>>) {
+pub fn on_hook(
+    _action: On<Hook>,
+    player: Single<(&mut Player, &mut PlayerState)>,
+    hooks: Query<&mut FishingHook>,
+) {
     info!("On Hook!");
-    let mut player = player.into_inner();
-    if *player == PlayerState::Fish {
-        *player = PlayerState::Hook;
-    } else if *player == PlayerState::Idle {
-        *player = PlayerState::Attack;
+    let (mut player, mut state) = player.into_inner();
+    if *state == PlayerState::Fish {
+        *state = PlayerState::Hook;
+        // The strike uses up the bait on the hook, rebait from the inventory.
+        for mut hook in hooks {
+            hook.strike = hook.bait.take();
+            hook.bait = player.take_bait();
+        }
+    } else if *state == PlayerState::Idle {
+        *state = PlayerState::Attack;
     }
 }
 
-pub fn on_sell(_action: On<Sell>, player: Single<&mut Player>) {
+pub fn on_sell(_action: On<Sell>, player: Single<&mut Player>, mut commands: Commands) {
     info!("On Sell!");
     let mut player = player.into_inner();
-    while let Some(item) = player.items.pop() {
-        info!("Sold item: {}", item.name());
-        player.money += item.value();
+    let items = std::mem::take(&mut player.items);
+    let mut sold = Vec::new();
+    for item in items {
+        if let items::Item::Fish(fish) = item {
+            info!("Sold item: {}", fish.name());
+            player.money += fish.value();
+            sold.push(fish);
+        } else {
+            player.items.push(item);
+        }
+    }
+    if !sold.is_empty() {
+        commands.trigger(Sold { fish: sold });
+    }
+}
+
+pub fn on_inspection(
+    _action: On<Inspection>,
+    calendar: Res<Calendar>,
+    regulations: Res<Regulations>,
+    records: Res<Records>,
+    player: Single<&mut Player>,
+) {
+    info!("On Inspection!");
+    let mut player = player.into_inner();
+    let illegal = regulations.check(&player, &records, calendar.day());
+    if illegal.is_empty() {
+        info!("Inspection passed");
+        return;
+    }
+    // Confiscate from the back so the indices stay valid.
+    for (index, reason) in illegal.iter().rev() {
+        let item = player.items.remove(*index);
+        info!("Confiscated {}: {}", item.name(), reason);
+    }
+    let fine = player.pay_fine(regulations.fine * illegal.len() as f32);
+    info!("Fined {:.2}", fine);
+    player.add_reputation(-K_OVERFISHING_REPUTATION * illegal.len() as f32);
+}
+
+pub fn on_release(
+    release: On<Release>,
+    regulations: Res<Regulations>,
+    species: Res<FishSpecies>,
+    viewport: Res<ViewportConfig>,
+    mut records: ResMut<Records>,
+    player: Single<&mut Player>,
+    boat: Single<&GlobalTransform, With<Boat>>,
+    fauna: Single<Entity, With<FaunaLayer>>,
+    mut commands: Commands,
+) {
+    info!("On Release!");
+    let mut player = player.into_inner();
+    let Some(items::Item::Fish(fish)) = player.items.get(release.index).cloned() else {
+        return;
+    };
+    player.items.remove(release.index);
+    records.release(&fish);
+
+    // Small fish handle the hook well, the others may not make it.
+    let mut rng = rand::thread_rng();
+    let undersized = regulations
+        .min_weights
+        .get(&fish.t)
+        .is_some_and(|min| fish.weight < *min);
+    commands.trigger(Released {
+        fish: fish.clone(),
+        juvenile: undersized,
+    });
+    if !undersized && rng.gen_range(0.0..1.0) < K_RELEASE_MORTALITY {
+        info!("Released {} did not survive", fish.t.name());
+        return;
     }
+    player.add_reputation(K_RELEASE_REPUTATION);
+    let Some(def) = species.get(fish.t) else {
+        return;
+    };
+
+    info!("Released {}", fish.t.name());
+    // The fauna layer doesn't scroll, so its positions are world positions. Fish released
+    // from the shore go back to the nearest water.
+    let position = Vec2::new(
+        (boat.translation().x + rng.gen_range(-32.0..32.0))
+            .clamp(K_FISH_AREA_BORDER, K_FISH_AREA_BORDER + K_FISH_AREA_SIZE),
+        viewport.ground_level - fish.t.depth().start,
+    );
+    let id = spawn_fish(
+        &mut commands,
+        def,
+        position,
+        rng.gen_bool(0.5),
+        viewport.ground_level,
+    );
+    commands.entity(*fauna).add_child(id);
 }
 
 pub fn on_hit(
     _action: On<Hit>,
     player: Single<&GlobalTransform, With<Player>>,
+    npcs: Query<(&GlobalTransform, &Npc, &ActionRange)>,
     buildings: Query<(&GlobalTransform, &Name, &ActionRange), With<Building>>,
     mut commands: Commands,
 ) {
     info!("On Hit!");
     let position = player.translation();
+    for (transform, npc, action_range) in npcs.iter() {
+        let distance = (position.x - transform.translation().x).abs();
+        if distance <= action_range.range {
+            info!("Talk: {}", npc.dialogue);
+            commands.trigger(Talk {
+                dialogue: npc.dialogue.clone(),
+            });
+            return;
+        }
+    }
     for (transform, name, action_range) in buildings.iter() {
         let distance = (position.x - transform.translation().x).abs();
         if distance <= action_range.range {
@@ -71,23 +235,72 @@ pub fn on_hit(
 
 pub fn on_catch(
     _action: On<Catch>,
-    player: Single<(&mut Player, &GlobalTransform)>,
+    calendar: Res<Calendar>,
+    regulations: Res<Regulations>,
+    stats: Res<FishStats>,
+    mut records: ResMut<Records>,
+    player: Single<&mut Player>,
+    hook: Single<(&Transform, &mut FishingHook)>,
+    areas: Query<(&ProtectedArea, &GlobalTransform)>,
     fishes: Query<(Entity, &GlobalTransform, &ActionRange, &Fish), With<Fish>>,
     mut commands: Commands,
 ) {
     info!("On Catch!");
-    let (mut player, player_transform) = player.into_inner();
-    let position = player_transform.translation();
+    let mut player = player.into_inner();
+    let (hook_transform, mut hook) = hook.into_inner();
+    let position = hook_transform.translation.truncate();
+    let mut rng = rand::thread_rng();
     for (entity, transform, action_range, fish) in fishes.iter() {
-        let distance = (position.x - transform.translation().x).abs();
+        let distance = position.distance(transform.translation().truncate());
         if distance <= action_range.range {
+            let attraction = hook.strike.map_or(1.0, |bait| bait.attraction(&fish.t));
+            let chance: f32 = rng.gen_range(0.0..1.0);
+            if chance >= K_BITE_CHANCE * attraction {
+                info!("No bite: {}", fish.t.name());
+                continue;
+            }
             info!("Catch fish: {}", fish.t.name());
             if player.items.len() < K_INVENTORY_SIZE {
                 commands.entity(entity).despawn();
-                player.items.push(items::Item::Fish(items::Fish {
-                    t: fish.t.clone(),
-                    weight: 1.0,
-                }));
+                let protection = areas
+                    .iter()
+                    .find(|(area, area_transform)| {
+                        area.contains(area_transform.translation(), hook_transform.translation)
+                    })
+                    .map(|(area, _)| area.protection);
+                if let Some(protection) = protection {
+                    info!("Caught in a protected area: {:?}", protection);
+                    player.add_reputation(-K_PROTECTED_REPUTATION);
+                    if protection == Protection::Banned {
+                        let fine = player.pay_fine(regulations.fine);
+                        info!("Fined {:.2}", fine);
+                    }
+                }
+                let landed = items::Fish {
+                    t: fish.t,
+                    weight: rng.gen_range(fish.t.weight()),
+                    day: calendar.day(),
+                };
+                commands.trigger(Landed {
+                    fish: landed.clone(),
+                });
+                player.items.push(items::Item::Fish(landed.clone()));
+                records.bag(&landed);
+                hook.landed = Some(landed);
+
+                let index = player.items.len() - 1;
+                let legal = regulations
+                    .check(&player, &records, calendar.day())
+                    .iter()
+                    .all(|(illegal, _)| *illegal != index);
+                let overfished = (stats.count(fish.t) as f32)
+                    < K_OVERFISHED_RATIO * fish.t.max_population() as f32;
+                if overfished {
+                    info!("{} is overfished", fish.t.name());
+                    player.add_reputation(-K_OVERFISHING_REPUTATION);
+                } else if legal && protection.is_none() {
+                    player.add_reputation(K_SUSTAINABLE_REPUTATION);
+                }
             } else {
                 info!("Inventory is full");
             }
@@ -95,22 +308,57 @@ pub fn on_catch(
     }
 }
 
-pub fn on_action(_action: On<Action>, player: Single<(&mut PlayerState, &GlobalTransform)>) {
+pub fn on_action(
+    _action: On<Action>,
+    viewport: Res<ViewportConfig>,
+    player: Single<(&mut Player, &mut PlayerState, &GlobalTransform)>,
+    mut commands: Commands,
+) {
     info!("On Action!");
-    let (mut state, player_transform) = player.into_inner();
+    let (mut player, mut state, player_transform) = player.into_inner();
     let position = player_transform.translation();
 
     *state = PlayerState::Idle;
     if position.x > K_OCEAN_LAND_BORDER {
         *state = PlayerState::Fish;
+
+        info!("Cast hook!");
+        commands
+            .spawn((
+                Sprite::from_color(Color::srgb(0.2, 0.2, 0.2), Vec2::new(6.0, 6.0)),
+                Transform::from_xyz(position.x, viewport.ground_level - K_HOOK_CAST_DEPTH, 5.2),
+                FishingHook {
+                    depth: K_HOOK_CAST_DEPTH,
+                    still: 0.0,
+                    bait: player.take_bait(),
+                    strike: None,
+                    landed: None,
+                },
+                Name::new("Hook"),
+            ))
+            .with_child((
+                Sprite::from_color(Color::srgb(0.9, 0.9, 0.9), Vec2::new(1.0, 0.0)),
+                Transform::from_xyz(0.0, 0.0, -0.1),
+                FishingLine,
+                Name::new("Line"),
+            ));
     }
 }
 
-pub fn on_end_action(_action: On<EndAction>, player: Single<(&mut Player, &mut PlayerState)>) {
+pub fn on_end_action(
+    _action: On<EndAction>,
+    player: Single<(&mut Player, &mut PlayerState)>,
+    hooks: Query<Entity, With<FishingHook>>,
+    mut commands: Commands,
+) {
     let (_, mut state) = player.into_inner();
 
     info!("On EndAction!");
 
+    for entity in hooks.iter() {
+        commands.entity(entity).despawn();
+    }
+
     if *state == PlayerState::Fish {
         *state = PlayerState::Row;
     } else {
@@ -124,13 +372,139 @@ pub fn on_end_action(_action: On<EndAction>, player: Single<(&mut Player, &mut P
 
 pub fn menu_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    calendar: Res<Calendar>,
+    mut records: ResMut<Records>,
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
+    player: Single<(&mut Player, &GlobalTransform)>,
+    buildings: Query<(&GlobalTransform, &ActionRange), With<Building>>,
 ) {
     if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Tab) {
         info!("Back in Game!");
         next_state.set(GameState::InGame);
         return;
     }
+
+    if keyboard_input.just_pressed(KeyCode::KeyJ) {
+        info!("Journal!");
+        next_state.set(GameState::InJournal);
+        return;
+    }
+
+    let (mut player, player_transform) = player.into_inner();
+    let at_hut = near_building(player_transform, &buildings);
+
+    let mut buy = None;
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        buy = Some(items::BaitType::Worm);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        buy = Some(items::BaitType::Shrimp);
+    }
+    if let Some(t) = buy {
+        let price = K_BAIT_PACK as f32 * t.value() * player.discount();
+        if !at_hut {
+            info!("Bait is sold at the hut");
+        } else if player.money < price {
+            info!("Not enough money for {}", t.name());
+        } else if player.add_bait(t, K_BAIT_PACK) {
+            info!("Bought bait: {}", t.name());
+            player.money -= price;
+        } else {
+            info!("Inventory is full");
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        let fish = player
+            .items
+            .iter()
+            .position(|item| matches!(item, items::Item::Fish(_)));
+        if let Some(index) = fish {
+            commands.trigger(Release { index });
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        let day = calendar.day();
+        if !at_hut {
+            info!("Licenses are sold at the hut");
+        } else if player.money < K_LICENSE_PRICE * player.discount() {
+            info!("Not enough money for a license");
+        } else {
+            // A new license extends the current one.
+            let last = player.extend_license(day);
+            player.money -= K_LICENSE_PRICE * player.discount();
+            info!("Licensed until day {}", last);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        let unmounted: Vec<items::FishType> = records
+            .best
+            .keys()
+            .filter(|t| !records.mounted.contains(*t))
+            .copied()
+            .collect();
+        if !at_hut {
+            info!("Records are mounted at the hut");
+        } else if unmounted.is_empty() {
+            info!("No record to mount");
+        } else {
+            info!("Mounted records: {:?}", unmounted);
+            records.mounted.extend(unmounted);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        let fish = player
+            .items
+            .iter()
+            .position(|item| matches!(item, items::Item::Fish(_)));
+        if let Some(index) = fish {
+            // The fish slot is freed before the bait is stacked.
+            let item = player.items.remove(index);
+            info!("Cut {} into bait", item.name());
+            player.add_bait(items::BaitType::FishChunk, K_BAIT_PER_FISH);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        // Cycle through the bait types in the inventory, then none.
+        let owned: Vec<items::BaitType> = player
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                items::Item::Bait(bait) => Some(bait.t),
+                _ => None,
+            })
+            .collect();
+        let next = match player.bait.and_then(|t| owned.iter().position(|o| *o == t)) {
+            Some(index) => owned.get(index + 1).copied(),
+            None => owned.first().copied(),
+        };
+        info!("Equipped bait: {:?}", next);
+        player.bait = next;
+    }
+}
+
+pub fn near_building(
+    player: &GlobalTransform,
+    buildings: &Query<(&GlobalTransform, &ActionRange), With<Building>>,
+) -> bool {
+    buildings.iter().any(|(transform, action_range)| {
+        (player.translation().x - transform.translation().x).abs() <= action_range.range
+    })
+}
+
+pub fn stats_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::KeyP) {
+        info!("Back in Game!");
+        next_state.set(GameState::InGame);
+    }
 }
 
 pub fn game_input(
@@ -152,6 +526,12 @@ pub fn game_input(
         return;
     }
 
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        info!("Population statistics!");
+        next_state.set(GameState::InStats);
+        return;
+    }
+
     let mut vel = 0.0;
     if keyboard_input.pressed(KeyCode::KeyA) {
         vel += 1.0;
@@ -182,13 +562,60 @@ pub fn game_input(
 }
 
 pub fn action_input(
+    time: Res<Time<Virtual>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut hooks: Query<&mut FishingHook>,
+    player: Single<&Player>,
+    // Seconds W or S has been held
+    mut held: Local<f32>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keyboard_input.just_released(KeyCode::KeyW) || keyboard_input.just_released(KeyCode::KeyS) {
+    let keys = [KeyCode::KeyW, KeyCode::KeyS];
+    // A tap strikes, holding the key moves the hook without striking.
+    if keyboard_input.any_just_released(keys) && *held < K_HOOK_TAP_SECS {
         commands.trigger(Hook);
     }
+    *held = if keyboard_input.any_pressed(keys) {
+        *held + time.delta_secs()
+    } else {
+        0.0
+    };
+
+    let mut reel = 0.0;
+    if *held >= K_HOOK_TAP_SECS {
+        if keyboard_input.pressed(KeyCode::KeyW) {
+            reel -= 1.0;
+        }
+        if keyboard_input.pressed(KeyCode::KeyS) {
+            reel += 1.0;
+        }
+    }
+    for mut hook in hooks.iter_mut() {
+        hook.depth =
+            (hook.depth + K_HOOK_SPEED * time.delta_secs() * reel).clamp(0.0, K_HOOK_MAX_DEPTH);
+        hook.still = if reel == 0.0 {
+            hook.still + time.delta_secs()
+        } else {
+            0.0
+        };
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        // Release the fish just landed, if it is still in the inventory.
+        for mut hook in hooks.iter_mut() {
+            let Some(landed) = hook.landed.take() else {
+                continue;
+            };
+            let index = player
+                .items
+                .iter()
+                .position(|item| matches!(item, items::Item::Fish(fish) if *fish == landed));
+            if let Some(index) = index {
+                commands.trigger(Release { index });
+            }
+        }
+    }
 
     if keyboard_input.just_released(KeyCode::Space) {
         info!("End action!");
@@ -198,39 +625,223 @@ pub fn action_input(
     }
 }
 
-pub fn ai_input(
-    ai: Res<AITimer>,
-    query: Query<(&mut Velocity, Option<&mut Direction>, &Transform), With<OnAI>>,
+pub fn inspection(
+    time: Res<Time<Virtual>>,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut commands: Commands,
 ) {
-    for (mut velocity, direction, transform) in query {
-        let mut vel = velocity.value;
+    // The coast guard only checks boats at sea.
+    if player.translation().x <= K_OCEAN_LAND_BORDER {
+        return;
+    }
+    let chance = (K_INSPECTION_RATE * time.delta_secs()).min(1.0);
+    if rand::thread_rng().gen_bool(chance as f64) {
+        info!("Inspection!");
+        commands.trigger(Inspection);
+    }
+}
 
-        if ai.timer.just_finished() {
+pub fn ai_input(query: Query<(&mut Steering, &Brain, &DecisionTimer), With<OnAI>>) {
+    for (mut steering, brain, decision) in query {
+        if brain.active != Behaviour::Wander {
+            continue;
+        }
+        let mut desired = steering.desired;
+
+        if decision.timer.just_finished() {
             let mut rng = rand::thread_rng();
             let chance: f32 = rng.gen_range(0.0..1.0);
+            let angle: f32 = rng.gen_range(-K_FISH_MAX_TILT..K_FISH_MAX_TILT);
+            desired = Vec2::new(angle.cos(), angle.sin());
             if chance < 0.5 {
-                vel = 1.0;
-            } else {
-                vel = -1.0
-            };
+                desired.x = -desired.x;
+            }
+        }
+
+        steering.desired = desired.normalize_or_zero() * K_SPEED;
+    }
+}
+
+pub fn fish_grid(mut grid: ResMut<FishGrid>, query: Query<(Entity, &Transform, &Swim, &Fish)>) {
+    grid.clear();
+    for (entity, transform, swim, fish) in query {
+        grid.insert(GridEntry {
+            entity,
+            position: transform.translation.truncate(),
+            velocity: swim.velocity,
+            t: fish.t,
+        });
+    }
+}
+
+pub fn ai_school(
+    grid: Res<FishGrid>,
+    query: Query<(Entity, &mut Steering, &Transform, &Fish, &Brain), With<OnAI>>,
+) {
+    for (entity, mut steering, transform, fish, brain) in query {
+        if !fish.t.schools() || brain.active != Behaviour::Wander {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let mut separation = Vec2::ZERO;
+        let mut alignment = Vec2::ZERO;
+        let mut center = Vec2::ZERO;
+        let mut count = 0;
+        for other in grid.neighbours(position, K_SCHOOL_RADIUS) {
+            if other.entity == entity || other.t != fish.t {
+                continue;
+            }
+            let offset = position - other.position;
+            let distance = offset.length();
+            if distance > K_SCHOOL_RADIUS {
+                continue;
+            }
+            if distance > 0.0 && distance < K_SCHOOL_SEPARATION {
+                separation += offset / distance * (1.0 - distance / K_SCHOOL_SEPARATION);
+            }
+            alignment += other.velocity.normalize_or_zero();
+            center += other.position;
+            count += 1;
+        }
+        if count == 0 {
+            continue;
+        }
+
+        let alignment = alignment / count as f32;
+        let cohesion = (center / count as f32 - position) / K_SCHOOL_RADIUS;
+        let steer = separation * K_SCHOOL_SEPARATION_WEIGHT
+            + alignment * K_SCHOOL_ALIGNMENT_WEIGHT
+            + cohesion * K_SCHOOL_COHESION_WEIGHT;
+        steering.desired = (steering.desired.normalize_or_zero() + steer).normalize_or_zero()
+            * steering.desired.length();
+    }
+}
+
+pub fn ai_lure(
+    hook: Query<(&Transform, &FishingHook)>,
+    query: Query<(&mut Senses, &Transform, &Fish), With<OnAI>>,
+) {
+    let Some((hook_transform, hook)) = hook.iter().next() else {
+        return;
+    };
+    let still = hook.bait.map_or(K_HOOK_STILL_SECS, |bait| bait.lure_secs());
+    if hook.still < still {
+        return;
+    }
+
+    let target = hook_transform.translation.truncate();
+    for (mut senses, transform, fish) in query {
+        if target.distance(transform.translation.truncate()) > K_LURE_RADIUS {
+            continue;
+        }
+        let attraction = hook.bait.map_or(1.0, |bait| bait.attraction(&fish.t));
+        senses.target(Stimulus {
+            position: target,
+            score: K_LURE_SCORE * (fish.t.curiosity() * attraction).min(1.0),
+            speed: K_LURE_SPEED,
+        });
+    }
+}
+
+pub fn ai_spook(
+    boat: Query<(&GlobalTransform, &Velocity), (With<Boat>, With<OnControl>)>,
+    query: Query<(&mut Senses, &Transform), With<OnAI>>,
+) {
+    let Some((boat_transform, boat_velocity)) = boat.iter().next() else {
+        return;
+    };
+    // The boat either moves at full speed or stands still.
+    if boat_velocity.value == 0.0 {
+        return;
+    }
+
+    let boat_position = boat_transform.translation().truncate();
+    for (mut senses, transform) in query {
+        let distance = boat_position.distance(transform.translation.truncate());
+        if distance < K_SPOOK_RADIUS {
+            senses.threat(Stimulus {
+                position: boat_position,
+                score: 1.0 - distance / K_SPOOK_RADIUS,
+                speed: K_FLEE_SPEED,
+            });
+        }
+    }
+}
+
+pub fn ai_flee(grid: Res<FishGrid>, query: Query<(&mut Senses, &Transform, &Fish), With<OnAI>>) {
+    for (mut senses, transform, fish) in query {
+        let position = transform.translation.truncate();
+        for other in grid.neighbours(position, K_FLEE_RADIUS) {
+            if !other.t.prey().contains(&fish.t) {
+                continue;
+            }
+            let distance = position.distance(other.position);
+            if distance < K_FLEE_RADIUS {
+                senses.threat(Stimulus {
+                    position: other.position,
+                    score: 1.0 - distance / K_FLEE_RADIUS,
+                    speed: K_FLEE_SPEED,
+                });
+            }
+        }
+    }
+}
+
+pub fn ai_hunt(
+    grid: Res<FishGrid>,
+    query: Query<(&mut Senses, &Transform, &Fish, &Hunger), With<OnAI>>,
+) {
+    for (mut senses, transform, fish, hunger) in query {
+        if hunger.time < K_HUNGRY_SECS {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let prey = grid
+            .neighbours(position, K_HUNT_RADIUS)
+            .filter(|other| fish.t.prey().contains(&other.t))
+            .map(|other| other.position)
+            .filter(|other| position.distance(*other) < K_HUNT_RADIUS)
+            .min_by(|a, b| position.distance(*a).total_cmp(&position.distance(*b)));
+        if let Some(prey) = prey {
+            // The hungrier, the more the hunt matters.
+            let starving = (hunger.time - K_HUNGRY_SECS) / (K_STARVE_SECS - K_HUNGRY_SECS);
+            senses.target(Stimulus {
+                position: prey,
+                score: 0.5 + 0.5 * starving.clamp(0.0, 1.0),
+                speed: K_HUNT_SPEED,
+            });
+        }
+    }
+}
+
+pub fn ai_bounds(
+    viewport: Res<ViewportConfig>,
+    query: Query<(&mut Steering, &Transform, &Fish), With<OnAI>>,
+) {
+    for (mut steering, transform, fish) in query {
+        let mut desired = steering.desired;
+
+        // Return to the preferred depth band.
+        let depth = viewport.ground_level - transform.translation.y;
+        let band = fish.t.depth();
+        if depth < band.start {
+            desired.y = -desired.y.abs().max(0.25 * K_SPEED);
+        }
+        if depth > band.end {
+            desired.y = desired.y.abs().max(0.25 * K_SPEED);
         }
 
         if transform.translation.x <= K_FISH_AREA_BORDER {
-            vel = -1.0;
+            desired.x = desired.x.abs();
         }
 
         if transform.translation.x >= K_FISH_AREA_BORDER + K_FISH_AREA_SIZE {
-            vel = 1.0;
+            desired.x = -desired.x.abs();
         }
 
-        velocity.value = vel;
-        if let Some(mut direction) = direction {
-            *direction = if vel > 0.0 {
-                Direction::Left
-            } else {
-                Direction::Right
-            };
-        }
+        steering.desired = desired.normalize_or_zero() * steering.desired.length();
     }
 }
 
@@ -271,6 +882,7 @@ pub fn changed_active_sprite(
 }
 
 pub fn changed_player_state(
+    viewport: Res<ViewportConfig>,
     state: Single<&PlayerState, (With<Player>, Changed<PlayerState>)>,
     mut set: ParamSet<(
         Single<(Entity, &mut ActiveSprite, &mut Transform), With<Player>>,
@@ -284,7 +896,7 @@ pub fn changed_player_state(
                 let (_entity, mut active, mut transform) = set.p0().into_inner();
                 info!("Stand and Walk!");
                 active.index = 0;
-                transform.translation.y = K_GROUND_LEVEL + 64.0;
+                transform.translation.y = viewport.ground_level + 64.0;
                 transform.translation.z = 0.0;
             }
             {
@@ -299,7 +911,7 @@ pub fn changed_player_state(
                 let (_entity, mut active, mut transform) = set.p0().into_inner();
                 info!("Sit and Row!");
                 active.index = 1;
-                transform.translation.y = K_GROUND_LEVEL + 64.0 + K_SIT_OFFSET;
+                transform.translation.y = viewport.ground_level + 64.0 + K_SIT_OFFSET;
                 transform.translation.z = 0.55;
             }
             {
@@ -313,14 +925,14 @@ pub fn changed_player_state(
             info!("Fish!");
             // TODO: Hard-coded values should be removed.
             active.index = 2;
-            transform.translation.y = K_GROUND_LEVEL + 64.0;
+            transform.translation.y = viewport.ground_level + 64.0;
             transform.translation.z = 0.0;
         }
         PlayerState::Idle => {
             let (_entity, mut active, mut transform) = set.p0().into_inner();
             info!("Idle!");
             active.index = 3;
-            transform.translation.y = K_GROUND_LEVEL + 64.0;
+            transform.translation.y = viewport.ground_level + 64.0;
             transform.translation.z = 0.0;
         }
         PlayerState::Hook => {
@@ -328,7 +940,7 @@ pub fn changed_player_state(
             let (_entity, mut active, mut transform) = set.p0().into_inner();
             // TODO: Hard-coded values should be removed.
             active.index = 4;
-            transform.translation.y = K_GROUND_LEVEL + 64.0;
+            transform.translation.y = viewport.ground_level + 64.0;
             transform.translation.z = 0.0;
         }
         PlayerState::Attack => {
@@ -336,7 +948,7 @@ pub fn changed_player_state(
             let (_entity, mut active, mut transform) = set.p0().into_inner();
             // TODO: Hard-coded values should be removed.
             active.index = 5;
-            transform.translation.y = K_GROUND_LEVEL + 64.0;
+            transform.translation.y = viewport.ground_level + 64.0;
             transform.translation.z = 0.0;
         }
     }
@@ -368,20 +980,17 @@ pub fn added_animation(
 
 pub fn changed_animation_player(
     query: Single<(&mut PlayerState, &AnimationState), Changed<AnimationState>>,
-    mut commands: Commands,
 ) {
     let (mut player_state, state) = query.into_inner();
     //info!("Changed State! {:?}", state);
     match *player_state {
         PlayerState::Hook => {
             if *state == AnimationState::Finish {
-                commands.trigger(Catch);
                 *player_state = PlayerState::Fish;
             }
         }
         PlayerState::Attack => {
             if *state == AnimationState::Finish {
-                commands.trigger(Hit);
                 *player_state = PlayerState::Idle;
             }
         }
@@ -393,6 +1002,18 @@ pub fn changed_animation_player(
 /// Update systems
 ///
 
+/// Triggers frame events for atlas indices in the `(from, to]` range.
+fn trigger_frame_events(config: &AnimationConfig, from: usize, to: usize, commands: &mut Commands) {
+    for frame in config.events.iter() {
+        if frame.index > from && frame.index <= to {
+            match frame.event {
+                AnimationEvent::Catch => commands.trigger(Catch),
+                AnimationEvent::Hit => commands.trigger(Hit),
+            }
+        }
+    }
+}
+
 pub fn animation(
     animations: Query<(
         &mut AnimationTimer,
@@ -400,12 +1021,17 @@ pub fn animation(
         &mut AnimationState,
         &AnimationConfig,
     )>,
+    mut commands: Commands,
 ) {
     for (mut animation, mut sprite, mut state, config) in animations.into_iter() {
         let size = config.last_index - config.first_index + 1;
 
         if animation.timer.is_finished() {
             if let Some(atlas) = &mut sprite.texture_atlas {
+                // Frames skipped by a long tick still fire their events.
+                if *state == AnimationState::Run {
+                    trigger_frame_events(config, atlas.index, config.last_index, &mut commands);
+                }
                 atlas.index = config.first_index;
             }
             *state = AnimationState::Finish;
@@ -420,47 +1046,190 @@ pub fn animation(
             let current = animation.timer.elapsed();
             let index = current.as_millis() as u64 / config.ms;
             if let Some(atlas) = &mut sprite.texture_atlas {
-                atlas.index = (config.first_index + index as usize)
+                let index = (config.first_index + index as usize)
                     .clamp(config.first_index, config.last_index);
+                trigger_frame_events(config, atlas.index, index, &mut commands);
+                atlas.index = index;
             }
         }
     }
 }
 
+pub fn open_sea_limit(
+    player: Single<(&Player, &mut Transform)>,
+    query: Query<&mut Transform, (With<OnControl>, Without<Player>)>,
+) {
+    let (player, mut transform) = player.into_inner();
+    if player.reputation >= K_OPEN_SEA_REPUTATION {
+        return;
+    }
+    // Push back the boat along with the player.
+    let overshoot = transform.translation.x - K_OPEN_SEA_BORDER;
+    if overshoot > 0.0 {
+        transform.translation.x -= overshoot;
+        for mut other in query {
+            other.translation.x -= overshoot;
+        }
+    }
+}
+
 pub fn move_control(
     time: Res<Time<Virtual>>,
-    query: Query<(&mut Transform, &Velocity, Option<&Camera>), With<OnControl>>,
+    query: Query<(&mut Transform, &Velocity), With<OnControl>>,
 ) {
-    for (mut transform, velocity, camera) in query {
+    for (mut transform, velocity) in query {
         transform.translation.x -= K_SPEED * time.delta_secs() * velocity.value;
-        if let Some(_) = camera {
-            let sig = (transform.translation.x - (K_FISH_AREA_BORDER)).signum();
-            transform.translation.y -= sig * K_SPEED * time.delta_secs();
-            transform.translation.y = transform.translation.y.clamp(-K_HEIGHT * 0.2, 0.0);
-        }
     }
 }
 
-pub fn move_layer(
+pub fn camera_follow(
     time: Res<Time<Virtual>>,
-    velocity: Single<&Velocity, With<Camera>>,
-    query: Query<(&mut Transform, &Layer)>,
+    viewport: Res<ViewportConfig>,
+    player: Single<(&GlobalTransform, &Velocity, &PlayerState), With<Player>>,
+    camera: Single<(&mut Transform, &mut Projection, &CameraController), Without<Player>>,
+) {
+    let (player_transform, player_velocity, state) = player.into_inner();
+    let (mut transform, mut projection, controller) = camera.into_inner();
+    let dt = time.delta_secs();
+    if dt == 0.0 {
+        return;
+    }
+
+    let mut scale = 1.0;
+    if let Projection::Orthographic(ortho) = projection.as_mut() {
+        let target = match state {
+            PlayerState::Fish | PlayerState::Hook => controller.fish_zoom,
+            _ => 1.0,
+        };
+        ortho.scale += (target - ortho.scale) * (1.0 - (-controller.zoom_damping * dt).exp());
+        scale = ortho.scale;
+    }
+
+    let position = player_transform.translation();
+    let mut target = Vec2::new(
+        position.x - player_velocity.value * controller.look_ahead,
+        0.0,
+    );
+    if position.x > K_FISH_AREA_BORDER {
+        target.y = controller.dive;
+    }
+
+    // Keep the visible area inside the world.
+    let half = viewport.size * scale / 2.0;
+    target.x = if K_WORLD_RIGHT - K_WORLD_LEFT > 2.0 * half.x {
+        target
+            .x
+            .clamp(K_WORLD_LEFT + half.x, K_WORLD_RIGHT - half.x)
+    } else {
+        (K_WORLD_LEFT + K_WORLD_RIGHT) / 2.0
+    };
+
+    let follow = 1.0 - (-controller.damping * dt).exp();
+    transform.translation.x += (target.x - transform.translation.x) * follow;
+    transform.translation.y += (target.y - transform.translation.y) * follow;
+}
+
+pub fn move_hook(
+    viewport: Res<ViewportConfig>,
+    player: Single<(&GlobalTransform, &Direction), With<Player>>,
+    hook: Single<(&mut Transform, &FishingHook), Without<FishingLine>>,
+    line: Single<(&mut Transform, &mut Sprite), With<FishingLine>>,
+) {
+    let (player_transform, direction) = player.into_inner();
+    let (mut hook_transform, hook) = hook.into_inner();
+    let (mut line_transform, mut line_sprite) = line.into_inner();
+
+    let sign = if *direction == Direction::Left {
+        -1.0
+    } else {
+        1.0
+    };
+    let position = player_transform.translation();
+    let tip = Vec2::new(
+        position.x + sign * K_ROD_OFFSET.x,
+        position.y + K_ROD_OFFSET.y,
+    );
+    let bottom = viewport.ground_level - hook.depth;
+
+    hook_transform.translation.x = tip.x;
+    hook_transform.translation.y = bottom;
+
+    // The line hangs from the rod tip down to the hook.
+    line_transform.translation.y = (tip.y - bottom) / 2.0;
+    line_sprite.custom_size = Some(Vec2::new(1.0, tip.y - bottom));
+}
+
+pub fn move_layer(
+    camera: Single<&Transform, With<Camera>>,
+    query: Query<(&mut Transform, &Layer), Without<Camera>>,
 ) {
+    // Speed 1.0 layers stay with the camera, speed 0.0 layers stay with the world.
     for (mut transform, layer) in query {
-        transform.translation.x -= K_SPEED * time.delta_secs() * velocity.value * layer.speed;
+        transform.translation.x = camera.translation.x * layer.speed;
+    }
+}
+
+pub fn wrap_tiled(
+    images: Res<Assets<Image>>,
+    camera: Single<&Transform, With<Camera>>,
+    layers: Query<&Transform, (With<Layer>, Without<Camera>)>,
+    query: Query<(&mut Transform, &Sprite, &ChildOf), (Without<Layer>, Without<Camera>)>,
+) {
+    for (mut transform, sprite, child_of) in query {
+        let SpriteImageMode::Tiled {
+            tile_x: true,
+            stretch_value,
+            ..
+        } = sprite.image_mode
+        else {
+            continue;
+        };
+        let (Some(image), Ok(layer)) = (images.get(&sprite.image), layers.get(child_of.parent()))
+        else {
+            continue;
+        };
+
+        // Shifting by whole tiles keeps the sprite centered on the camera without a seam.
+        let period = image.width() as f32 * stretch_value;
+        let center = camera.translation.x - layer.translation.x;
+        transform.translation.x -= period * ((transform.translation.x - center) / period).round();
     }
 }
 
 pub fn move_ai(
     time: Res<Time<Virtual>>,
-    query: Query<(&mut Transform, &Velocity, &Fish), With<OnAI>>,
+    viewport: Res<ViewportConfig>,
+    query: Query<(&mut Transform, &mut Swim, &Steering, &mut Direction), (With<OnAI>, With<Fish>)>,
 ) {
-    for (mut transform, velocity, _) in query {
-        transform.translation.x -= K_SPEED * time.delta_secs() * velocity.value;
+    let dt = time.delta_secs();
+    for (mut transform, mut swim, steering, mut direction) in query {
+        // Turn towards the desired heading at a limited rate.
+        let current = swim.velocity.to_angle();
+        let turn = (steering.desired.to_angle() - current + PI).rem_euclid(TAU) - PI;
+        let max_turn = K_FISH_TURN_RATE * dt;
+        let heading = current + turn.clamp(-max_turn, max_turn);
+        swim.velocity = Vec2::from_angle(heading) * steering.desired.length();
+
+        transform.translation += (swim.velocity * dt).extend(0.0);
         transform.translation.x = transform
             .translation
             .x
             .clamp(K_FISH_AREA_BORDER, K_FISH_AREA_BORDER + K_FISH_AREA_SIZE);
+        transform.translation.y = transform.translation.y.min(viewport.ground_level);
+
+        direction.set_if_neq(if swim.velocity.x < 0.0 {
+            Direction::Left
+        } else {
+            Direction::Right
+        });
+
+        // Tilt the sprite with the vertical heading, the sprite is mirrored when facing left.
+        let tilt = swim
+            .velocity
+            .y
+            .atan2(swim.velocity.x.abs())
+            .clamp(-K_FISH_MAX_TILT, K_FISH_MAX_TILT);
+        transform.rotation = Quat::from_rotation_z(tilt * swim.velocity.x.signum());
     }
 }
 
@@ -492,12 +1261,16 @@ pub fn animation_ai(time: Res<Time<Virtual>>, animations: Query<&mut AnimationTi
     }
 }
 
-pub fn move_sun(time: Res<Time<Virtual>>, mut sun_query: Single<&mut Transform, With<Sun>>) {
-    let day_time = (24.0 * time.elapsed_secs() / K_SECS_IN_DAY) % 24.0;
+pub fn move_sun(
+    calendar: Res<Calendar>,
+    viewport: Res<ViewportConfig>,
+    mut sun_query: Single<&mut Transform, With<Sun>>,
+) {
+    let day_time = calendar.hour();
     // Map to [0, 1] range.
     let day = (1.0 + (3.14 * day_time / 12.0 - 3.14 / 2.0).sin()) / 2.0;
 
-    sun_query.translation.y = day * K_HEIGHT - K_HEIGHT / 2.0;
+    sun_query.translation.y = day * viewport.size.y - viewport.size.y / 2.0;
 }
 
 pub fn move_cloud(time: Res<Time<Virtual>>, query: Query<(&mut Transform, &Cloud, &Sprite)>) {
@@ -506,7 +1279,6 @@ pub fn move_cloud(time: Res<Time<Virtual>>, query: Query<(&mut Transform, &Cloud
             match sprite.image_mode {
                 SpriteImageMode::Tiled { .. } => {
                     transform.translation.x += K_SPEED * cloud.speed * 1.0 * time.delta_secs();
-                    transform.translation.x = transform.translation.x % K_WIDTH;
                 }
                 _ => {}
             }
@@ -537,11 +1309,11 @@ pub fn player_state_walk_or_row(
 }
 
 pub fn color_day_night(
-    time: Res<Time<Virtual>>,
+    calendar: Res<Calendar>,
     query: Query<(&mut Sprite, &DefaultColor), With<DayNightColor>>,
 ) {
-    //info!("Time = {}", time.elapsed_secs());
-    let day_time = (24.0 * time.elapsed_secs() / K_SECS_IN_DAY) % 24.0;
+    //info!("Time = {}", calendar.secs);
+    let day_time = calendar.hour();
     // Map to [0, 1] range.
     let day = (1.0 + (3.14 * day_time / 12.0 - 3.14 / 2.0).sin()) / 2.0;
     //info!("Date = {}, light={}", day_time, day);
@@ -551,55 +1323,331 @@ pub fn color_day_night(
     }
 }
 
+/// Sizes the fitted sprites to the visible area, which grows with the camera zoom.
+pub fn layout_viewport(
+    viewport: Res<ViewportConfig>,
+    camera: Single<Ref<Projection>, With<CameraController>>,
+    query: Query<(&mut Sprite, &ViewportFit)>,
+) {
+    if !viewport.is_changed() && !camera.is_changed() {
+        return;
+    }
+    let scale = match &**camera {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.0,
+    };
+    let ratio = viewport.size * scale / Vec2::new(K_WIDTH, K_HEIGHT);
+    for (mut sprite, fit) in query {
+        sprite.custom_size = Some(fit.size * ratio);
+    }
+}
+
 ///
 /// Spawn systems
 ///
 
-pub fn fish_spawn(
-    ai: Res<AITimer>,
-    query: Query<(Entity, &Velocity, &Direction), With<Fish>>,
-    mut commands: Commands,
-) {
-    if ai.timer.just_finished() {
-        let population = query.iter().len();
-        if population < K_FISH_MAX_POPULATION {
-            for (entity, velocity, direction) in query {
-                let mut rng = rand::thread_rng();
-                let chance: f32 = rng.gen_range(0.0..1.0);
-                if chance < 0.1 {
-                    let oppose_direction = if *direction == Direction::Left {
-                        Direction::Right
-                    } else {
-                        Direction::Left
-                    };
-                    commands
-                        .entity(entity)
-                        .clone_and_spawn()
-                        .insert(Velocity {
-                            value: -velocity.value,
-                        })
-                        .insert(oppose_direction);
-                }
-            }
+pub fn fish_stats(calendar: Res<Calendar>, mut stats: ResMut<FishStats>, fish: Query<&Fish>) {
+    stats.counts.clear();
+    for fish in fish {
+        *stats.counts.entry(fish.t).or_default() += 1;
+    }
+
+    let day = calendar.day();
+    if day >= stats.day {
+        let counts = stats.counts.clone();
+        stats.history.push(counts);
+        if stats.history.len() > K_STATS_DAYS {
+            stats.history.remove(0);
         }
+        stats.day = day + 1;
     }
 }
 
-///
-/// Game state transition systems
-///
+/// Spawns a live fish of the species, the caller adds it to the fauna layer.
+fn spawn_fish(
+    commands: &mut Commands,
+    def: &SpeciesDef,
+    position: Vec2,
+    right: bool,
+    ground_level: f32,
+) -> Entity {
+    let mut entity = commands.spawn((
+        def.sprite.clone(),
+        def.animation.clone(),
+        AnimationTimer::default(),
+        Transform::from_xyz(position.x, position.y, 0.0),
+        Name::new(def.t.name()),
+    ));
+    ObjectComponentType::Fish(Fish { t: def.t }).insert(&mut entity, def.size);
+    if def.t.is_predator() {
+        // Predators cruise the whole area along the middle of their depth band.
+        let band = def.t.depth();
+        let y = ground_level - (band.start + band.end) / 2.0;
+        entity.insert(Patrol {
+            points: vec![
+                Vec2::new(K_FISH_AREA_BORDER + K_FISH_AREA_SIZE, y),
+                Vec2::new(K_FISH_AREA_BORDER, y),
+            ],
+            index: 0,
+        });
+    }
+    if right {
+        entity
+            .insert(Direction::Right)
+            .insert(Swim {
+                velocity: Vec2::new(K_SPEED, 0.0),
+            })
+            .insert(Steering {
+                desired: Vec2::new(K_SPEED, 0.0),
+            });
+    }
+    entity.id()
+}
 
-pub fn enter_player_menu(
+pub fn ecosystem_health(stats: Res<FishStats>, mut ecosystem: ResMut<Ecosystem>) {
+    *ecosystem = Ecosystem::from_stats(&stats);
+}
+
+pub fn fish_spawn(
+    population: Res<PopulationTimer>,
+    species: Res<FishSpecies>,
+    viewport: Res<ViewportConfig>,
+    mut stats: ResMut<FishStats>,
+    zones: Query<(&SpawnZone, &Transform, &ChildOf)>,
+    areas: Query<(&ProtectedArea, &GlobalTransform)>,
+    fish: Query<(&Transform, &Fish)>,
     mut commands: Commands,
-    mut time: ResMut<Time<Virtual>>,
-    player: Single<&Player>,
 ) {
-    info!("Creating player menu");
-    time.pause();
+    let mut rng = rand::thread_rng();
+    // The zones are filled up to their cap once per run, then refilled at their rate.
+    let seed = !stats.seeded;
+    stats.seeded = true;
+    for (zone, transform, child_of) in zones {
+        if !seed && !population.timer.just_finished() {
+            continue;
+        }
+
+        let center = transform.translation;
+        let inside = fish
+            .iter()
+            .filter(|(fish_transform, fish)| {
+                zone.spawns(&fish.t) && zone.contains(center, fish_transform.translation)
+            })
+            .count();
+        let mut missing = zone.cap.saturating_sub(inside);
+        // Protected areas recover faster: fish are rolled at the best recovery rate, then kept
+        // by the recovery of the area they land in.
+        let best = areas
+            .iter()
+            .map(|(area, _)| area.recovery)
+            .fold(1.0, f32::max);
+        let rolled = if seed {
+            missing
+        } else {
+            let expected = zone.rate * best * K_POPULATION_SECS;
+            expected.floor() as usize + (rng.gen_range(0.0..1.0) < expected.fract()) as usize
+        };
+
+        for _ in 0..rolled {
+            if missing == 0 {
+                break;
+            }
+            let Some(def) = zone
+                .pick(rng.gen_range(0.0..1.0))
+                .and_then(|t| species.get(t))
+            else {
+                continue;
+            };
+            if stats.count(def.t) >= def.t.max_population() {
+                continue;
+            }
+
+            let position = Vec2::new(
+                center.x + rng.gen_range(-0.5..0.5) * zone.width,
+                viewport.ground_level - rng.gen_range(def.t.depth()),
+            );
+            if !seed {
+                let recovery = ProtectedArea::recovery_at(
+                    areas
+                        .iter()
+                        .map(|(area, area_transform)| (area, area_transform.translation())),
+                    position.extend(0.0),
+                );
+                if rng.gen_range(0.0..1.0) >= recovery / best {
+                    continue;
+                }
+            }
+            let id = spawn_fish(
+                &mut commands,
+                def,
+                position,
+                rng.gen_bool(0.5),
+                viewport.ground_level,
+            );
+            commands.entity(child_of.parent()).add_child(id);
+            *stats.counts.entry(def.t).or_default() += 1;
+            missing -= 1;
+        }
+    }
+}
 
+pub fn zone_debug_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut zone_debug: ResMut<ZoneDebug>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        zone_debug.enabled = !zone_debug.enabled;
+        info!("Zone debug: {}", zone_debug.enabled);
+    }
+}
+
+pub fn zone_debug(zone_debug: Res<ZoneDebug>, zones: Query<&mut Visibility, With<SpawnZone>>) {
+    if !zone_debug.is_changed() {
+        return;
+    }
+    for mut visibility in zones {
+        *visibility = if zone_debug.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn fish_eat(
+    grid: Res<FishGrid>,
+    query: Query<(&Transform, &Fish, &mut Hunger)>,
+    mut commands: Commands,
+) {
+    let mut eaten = HashSet::new();
+    for (transform, fish, mut hunger) in query {
+        if hunger.time < K_HUNGRY_SECS {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let prey = grid.neighbours(position, K_EAT_RANGE).find(|other| {
+            fish.t.prey().contains(&other.t)
+                && !eaten.contains(&other.entity)
+                && position.distance(other.position) < K_EAT_RANGE
+        });
+        if let Some(prey) = prey {
+            info!("{} ate {}", fish.t.name(), prey.t.name());
+            eaten.insert(prey.entity);
+            commands.entity(prey.entity).try_despawn();
+            hunger.time = 0.0;
+        }
+    }
+}
+
+pub fn fish_hunger(
+    time: Res<Time<Virtual>>,
+    query: Query<(Entity, &Fish, &mut Hunger)>,
+    mut commands: Commands,
+) {
+    for (entity, fish, mut hunger) in query {
+        hunger.time += time.delta_secs();
+        if hunger.time > K_STARVE_SECS {
+            info!("{} starved", fish.t.name());
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+///
+/// Game state transition systems
+///
+
+fn spawn_player_menu(
+    commands: &mut Commands,
+    player: &Player,
+    at_hut: bool,
+    regulations: &Regulations,
+    records: &Records,
+    contracts: &[String],
+    day: u32,
+) {
     let money = format!("Money: {}", player.money.to_string());
     commands.spawn((Text::new(money), TextFont::from_font_size(48.0), PlayerMenu));
 
+    let bait = match player.bait {
+        Some(t) => t.name(),
+        None => String::from("None"),
+    };
+    let mut help = vec![
+        format!("Bait: {}", bait),
+        String::from("[E] Equip bait; [C] Cut a fish into bait; [R] Release a fish; [J] Journal"),
+        format!("Reputation: {:.1}", player.reputation),
+    ];
+    if at_hut {
+        help.push(format!(
+            "[B] Buy {} worms: {:.2}; [N] Buy {} shrimps: {:.2}",
+            K_BAIT_PACK,
+            K_BAIT_PACK as f32 * items::BaitType::Worm.value() * player.discount(),
+            K_BAIT_PACK,
+            K_BAIT_PACK as f32 * items::BaitType::Shrimp.value() * player.discount(),
+        ));
+        help.push(format!(
+            "[L] Buy a {} day license: {:.2}",
+            K_LICENSE_DAYS,
+            K_LICENSE_PRICE * player.discount()
+        ));
+        help.push(String::from("[M] Mount your records on the hut wall"));
+        help.extend(contracts.iter().cloned());
+    }
+
+    let license = match player.license {
+        Some(last) if player.has_license(day) => format!("until day {}", last),
+        _ => String::from("None"),
+    };
+    help.push(format!(
+        "Day {}, {:?}; License: {}",
+        day,
+        Season::from_day(day),
+        license
+    ));
+    for t in items::FishType::ALL {
+        let mut rules = Vec::new();
+        if let Some(limit) = regulations.bag_limits.get(&t) {
+            rules.push(format!("{} per day", limit));
+        }
+        if let Some(min) = regulations.min_weights.get(&t) {
+            rules.push(format!("min {:.2} kg", min));
+        }
+        if let Some(season) = regulations.closed_seasons.get(&t) {
+            rules.push(format!("closed in {:?}", season));
+        }
+        help.push(format!("{}: {}", t.name(), rules.join(", ")));
+    }
+
+    for t in items::FishType::ALL {
+        if let Some(best) = records.best.get(&t) {
+            let mounted = if records.mounted.contains(&t) {
+                " (mounted)"
+            } else {
+                ""
+            };
+            help.push(format!(
+                "Record {}: {:.2} kg on day {}{}",
+                t.name(),
+                best.weight,
+                best.day,
+                mounted
+            ));
+        }
+    }
+    let mut trophies: Vec<&str> = records
+        .trophies
+        .iter()
+        .map(|trophy| trophy.text())
+        .collect();
+    trophies.sort();
+    help.push(format!(
+        "Fish caught: {}; Trophies: {}",
+        records.caught,
+        trophies.join(", ")
+    ));
+
     commands
         .spawn((
             Node {
@@ -614,6 +1662,9 @@ pub fn enter_player_menu(
             BackgroundColor(Color::srgb(0.50, 0.50, 0.50)),
         ))
         .with_children(|parent| {
+            for line in help {
+                parent.spawn(Text::new(line));
+            }
             for item in &player.items {
                 parent.spawn((
                     Text::new(format!(
@@ -630,6 +1681,61 @@ pub fn enter_player_menu(
         });
 }
 
+pub fn enter_player_menu(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    calendar: Res<Calendar>,
+    regulations: Res<Regulations>,
+    records: Res<Records>,
+    board: Res<ContractBoard>,
+    contracts: Res<Contracts>,
+    player: Single<(&Player, &GlobalTransform)>,
+    buildings: Query<(&GlobalTransform, &ActionRange), With<Building>>,
+) {
+    info!("Creating player menu");
+    time.pause();
+
+    let (player, player_transform) = player.into_inner();
+    spawn_player_menu(
+        &mut commands,
+        player,
+        near_building(player_transform, &buildings),
+        &regulations,
+        &records,
+        &contracts::board_lines(&board, &contracts),
+        calendar.day(),
+    );
+}
+
+pub fn changed_player_menu(
+    mut commands: Commands,
+    calendar: Res<Calendar>,
+    regulations: Res<Regulations>,
+    records: Res<Records>,
+    board: Res<ContractBoard>,
+    contracts: Res<Contracts>,
+    menu_query: Query<Entity, With<PlayerMenu>>,
+    player: Single<(Ref<Player>, &GlobalTransform)>,
+    buildings: Query<(&GlobalTransform, &ActionRange), With<Building>>,
+) {
+    let (player, player_transform) = player.into_inner();
+    if !player.is_changed() && !records.is_changed() && !contracts.is_changed() {
+        return;
+    }
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_player_menu(
+        &mut commands,
+        &player,
+        near_building(player_transform, &buildings),
+        &regulations,
+        &records,
+        &contracts::board_lines(&board, &contracts),
+        calendar.day(),
+    );
+}
+
 pub fn exit_player_menu(
     mut commands: Commands,
     menu_query: Query<Entity, With<PlayerMenu>>,
@@ -641,3 +1747,594 @@ pub fn exit_player_menu(
     }
     time.unpause();
 }
+
+fn species_color(t: items::FishType) -> Color {
+    match t {
+        items::FishType::Fish => Color::srgb(1.0, 0.85, 0.2),
+        items::FishType::Ray => Color::srgb(0.3, 0.8, 1.0),
+        items::FishType::Shark => Color::srgb(1.0, 0.3, 0.3),
+    }
+}
+
+fn spawn_stats_screen(commands: &mut Commands, stats: &FishStats) {
+    commands
+        .spawn((
+            Node {
+                width: percent(50),
+                height: percent(50),
+                top: percent(25),
+                left: percent(25),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            StatsScreen,
+            BackgroundColor(Color::srgb(0.50, 0.50, 0.50)),
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text::new(format!(
+                "Population over the last {} days, in % of the species cap",
+                stats.history.len()
+            )));
+            for t in items::FishType::ALL {
+                parent.spawn((
+                    Text::new(format!(
+                        "{}: {} / {}",
+                        t.name(),
+                        stats.count(t),
+                        t.max_population()
+                    )),
+                    TextColor(species_color(t)),
+                ));
+            }
+
+            parent
+                .spawn((
+                    Node {
+                        width: px(K_CHART_SIZE.x),
+                        height: px(K_CHART_SIZE.y),
+                        margin: UiRect::all(px(8)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                ))
+                .with_children(|chart| {
+                    for t in items::FishType::ALL {
+                        // Daily samples followed by the live count.
+                        let counts: Vec<usize> = stats
+                            .history
+                            .iter()
+                            .map(|counts| counts.get(&t).copied().unwrap_or(0))
+                            .chain(std::iter::once(stats.count(t)))
+                            .collect();
+                        let step = K_CHART_SIZE.x / (counts.len() - 1).max(1) as f32;
+                        let points: Vec<Vec2> = counts
+                            .iter()
+                            .enumerate()
+                            .map(|(i, count)| {
+                                let ratio = (*count as f32 / t.max_population() as f32).min(1.0);
+                                Vec2::new(i as f32 * step, K_CHART_SIZE.y * (1.0 - ratio))
+                            })
+                            .collect();
+
+                        for segment in points.windows(2) {
+                            let delta = segment[1] - segment[0];
+                            let center = (segment[0] + segment[1]) / 2.0;
+                            let length = delta.length();
+                            chart.spawn((
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    left: px(center.x - length / 2.0),
+                                    top: px(center.y - 1.0),
+                                    width: px(length),
+                                    height: px(2),
+                                    ..default()
+                                },
+                                UiTransform::from_rotation(Rot2::radians(delta.y.atan2(delta.x))),
+                                BackgroundColor(species_color(t)),
+                            ));
+                        }
+                    }
+                });
+        });
+}
+
+pub fn enter_stats(mut commands: Commands, mut time: ResMut<Time<Virtual>>, stats: Res<FishStats>) {
+    info!("Creating statistics screen");
+    time.pause();
+    spawn_stats_screen(&mut commands, &stats);
+}
+
+pub fn exit_stats(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<StatsScreen>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    info!("Removing statistics screen");
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    time.unpause();
+}
+
+pub fn journal_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::KeyJ) {
+        info!("Back to the player menu!");
+        next_state.set(GameState::InPlayerMenu);
+    }
+}
+
+/// One entry per species from its definition, unseen species as silhouettes.
+fn spawn_journal_screen(commands: &mut Commands, species: &FishSpecies, records: &Records) {
+    commands
+        .spawn((
+            Node {
+                width: percent(60),
+                height: percent(80),
+                top: percent(10),
+                left: percent(20),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            JournalScreen,
+            BackgroundColor(Color::srgb(0.50, 0.50, 0.50)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("Journal"), TextFont::from_font_size(28.0)));
+            for def in &species.species {
+                let t = def.t;
+                let seen = records.landed(t) > 0;
+                let mut image = ImageNode::new(def.sprite.image.clone());
+                if let Some(atlas) = &def.sprite.texture_atlas {
+                    image = ImageNode::from_atlas_image(def.sprite.image.clone(), atlas.clone());
+                }
+                if !seen {
+                    image = image.with_color(Color::BLACK);
+                }
+
+                let lines = if seen {
+                    vec![
+                        t.name(),
+                        format!("Depth: {:.0}-{:.0}", t.depth().start, t.depth().end),
+                        format!(
+                            "Fishermen say it feeds {} and bites best in {:?}.",
+                            t.active_hours().text(),
+                            t.season()
+                        ),
+                        format!(
+                            "Value: {:.2} per kg; Caught: {}; Released: {}",
+                            t.value(),
+                            records.landed(t),
+                            records.released(t)
+                        ),
+                    ]
+                } else {
+                    vec![
+                        String::from("???"),
+                        String::from("Catch one to learn more."),
+                    ]
+                };
+
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(px(8)),
+                        ..default()
+                    })
+                    .with_children(|entry| {
+                        entry.spawn((
+                            image,
+                            Node {
+                                width: px(def.size.x),
+                                height: px(def.size.y),
+                                margin: UiRect::right(px(16)),
+                                ..default()
+                            },
+                        ));
+                        entry
+                            .spawn(Node {
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            })
+                            .with_children(|text| {
+                                for line in lines {
+                                    text.spawn(Text::new(line));
+                                }
+                            });
+                    });
+            }
+        });
+}
+
+pub fn enter_journal(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    species: Res<FishSpecies>,
+    records: Res<Records>,
+) {
+    info!("Creating journal");
+    time.pause();
+    spawn_journal_screen(&mut commands, &species, &records);
+}
+
+pub fn exit_journal(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<JournalScreen>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    info!("Removing journal");
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    time.unpause();
+}
+
+pub fn milestones(
+    player: Single<&Player>,
+    calendar: Res<Calendar>,
+    ecosystem: Res<Ecosystem>,
+    progress: Res<Progress>,
+    mut commands: Commands,
+) {
+    let mut reached = Vec::new();
+    if player.reputation >= K_OPEN_SEA_REPUTATION {
+        reached.push(Milestone::OpenSea);
+    }
+    if player.reputation >= K_DISCOUNT_REPUTATION {
+        reached.push(Milestone::TrustedFisher);
+    }
+    if EndConditions::settled(calendar.day()) && ecosystem.health < K_DECLINE_HEALTH {
+        reached.push(Milestone::EcosystemDecline);
+    }
+    if ecosystem.health >= K_THRIVING_HEALTH && player.reputation >= K_DISCOUNT_REPUTATION {
+        reached.push(Milestone::EcosystemThriving);
+    }
+    for milestone in reached {
+        if !progress.reached.contains(&milestone) {
+            commands.trigger(Story { milestone });
+        }
+    }
+}
+
+pub fn on_story(story: On<Story>, mut progress: ResMut<Progress>) {
+    info!("Story: {}", story.milestone.text());
+    progress.reached.insert(story.milestone);
+    progress.message = Some(story.milestone.text().to_string());
+}
+
+pub fn on_landed(landed: On<Landed>, mut records: ResMut<Records>, mut progress: ResMut<Progress>) {
+    let fish = &landed.fish;
+    if records.record(fish) {
+        info!("New record: {} {:.2} kg", fish.t.name(), fish.weight);
+        progress.message = Some(format!(
+            "New record: {} of {:.2} kg!",
+            fish.t.name(),
+            fish.weight
+        ));
+    }
+    for trophy in records.earned() {
+        if records.trophies.insert(trophy) {
+            info!("Trophy: {}", trophy.text());
+            progress.message = Some(format!("Trophy: {}!", trophy.text()));
+        }
+    }
+}
+
+/// Shows the mounted records on the hut wall, heavier fish mounted larger.
+pub fn trophy_wall(
+    records: Res<Records>,
+    species: Res<FishSpecies>,
+    huts: Query<Entity, With<Building>>,
+    trophies: Query<Entity, With<MountedTrophy>>,
+    mut commands: Commands,
+) {
+    if !records.is_changed() {
+        return;
+    }
+    for entity in trophies {
+        commands.entity(entity).despawn();
+    }
+    let Some(hut) = huts.iter().next() else {
+        return;
+    };
+
+    let mounted = items::FishType::ALL
+        .iter()
+        .filter(|t| records.mounted.contains(*t))
+        .filter_map(|t| Some((records.best.get(t)?, species.get(*t)?)));
+    for (index, (fish, def)) in mounted.enumerate() {
+        let range = fish.t.weight();
+        let scale = 0.75 + 0.5 * (fish.weight - range.start) / (range.end - range.start);
+        let mut sprite = def.sprite.clone();
+        sprite.custom_size = Some(def.size * scale);
+        let trophy = commands
+            .spawn((
+                sprite,
+                Transform::from_xyz(
+                    K_TROPHY_WALL.x + index as f32 * K_TROPHY_SPACING,
+                    K_TROPHY_WALL.y,
+                    0.1,
+                ),
+                MountedTrophy,
+                Name::new(format!("Trophy - {}", fish.t.name())),
+            ))
+            .id();
+        commands.entity(hut).add_child(trophy);
+    }
+}
+
+pub fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(20.0),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(8),
+            left: px(8),
+            ..default()
+        },
+        Hud,
+    ));
+}
+
+pub fn update_hud(
+    player: Single<&Player>,
+    ecosystem: Res<Ecosystem>,
+    progress: Res<Progress>,
+    hud: Single<&mut Text, With<Hud>>,
+) {
+    let mut text = hud.into_inner();
+    let mut hud = format!(
+        "Money: {:.2}  Reputation: {:.1}  Ecosystem: {:.0}%",
+        player.money,
+        player.reputation,
+        100.0 * ecosystem.health
+    );
+    if let Some(message) = &progress.message {
+        hud.push('\n');
+        hud.push_str(message);
+    }
+    if text.0 != hud {
+        text.0 = hud;
+    }
+}
+
+pub fn end_conditions(
+    calendar: Res<Calendar>,
+    stats: Res<FishStats>,
+    ecosystem: Res<Ecosystem>,
+    conditions: Res<EndConditions>,
+    player: Single<&Player>,
+    mut outcome: ResMut<GameOutcome>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let day = calendar.day();
+    let total: usize = stats.counts.values().sum();
+    let species = stats.counts.values().filter(|count| **count > 0).count();
+    let result = conditions.outcome(day, total, species, player.money, ecosystem.health);
+
+    if let Some(result) = result {
+        info!("Run over: {:?}", result);
+        outcome.outcome = Some(result);
+        next_state.set(GameState::InSummary);
+    }
+}
+
+pub fn enter_summary(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    outcome: Res<GameOutcome>,
+    player: Single<&Player>,
+    calendar: Res<Calendar>,
+    stats: Res<FishStats>,
+    ecosystem: Res<Ecosystem>,
+    progress: Res<Progress>,
+) {
+    info!("Creating summary screen");
+    time.pause();
+
+    let title = outcome
+        .outcome
+        .map_or("Game over", |outcome| outcome.text());
+    let mut lines = vec![
+        format!("Day {}", calendar.day()),
+        format!("Money: {:.2}", player.money),
+        format!("Reputation: {:.1}", player.reputation),
+        format!(
+            "Ecosystem: {:.0}% (diversity {:.0}%, abundance {:.0}%)",
+            100.0 * ecosystem.health,
+            100.0 * ecosystem.diversity,
+            100.0 * ecosystem.abundance
+        ),
+    ];
+    for t in items::FishType::ALL {
+        lines.push(format!(
+            "{}: {} / {}",
+            t.name(),
+            stats.count(t),
+            t.max_population()
+        ));
+    }
+    lines.push(format!("Milestones: {}", progress.reached.len()));
+    lines.push(String::from("[Enter] Restart; [L] Load; [Esc] Quit"));
+
+    commands
+        .spawn((
+            Node {
+                width: percent(50),
+                height: percent(50),
+                top: percent(25),
+                left: percent(25),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            SummaryScreen,
+            BackgroundColor(Color::srgb(0.50, 0.50, 0.50)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(title), TextFont::from_font_size(28.0)));
+            for line in lines {
+                parent.spawn(Text::new(line));
+            }
+        });
+}
+
+pub fn summary_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut pending: ResMut<PendingLoad>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit_events: MessageWriter<AppExit>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        info!("Quitting app!");
+        app_exit_events.write(AppExit::Success);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        next_state.set(GameState::Restart);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        match SaveGame::read() {
+            Ok(save) => {
+                pending.save = Some(save);
+                next_state.set(GameState::Restart);
+            }
+            Err(error) => info!("Load failed: {}", error),
+        }
+    }
+}
+
+pub fn exit_summary(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<SummaryScreen>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    info!("Removing summary screen");
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    time.unpause();
+}
+
+pub fn save_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    calendar: Res<Calendar>,
+    stats: Res<FishStats>,
+    progress: Res<Progress>,
+    records: Res<Records>,
+    contracts: Res<Contracts>,
+    viewport: Res<ViewportConfig>,
+    player: Single<&Player>,
+    fish: Query<(&Fish, &Transform, &Direction)>,
+    mut pending: ResMut<PendingLoad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        let save = SaveGame {
+            secs: calendar.secs,
+            player: SavedPlayer::from_player(&player),
+            fish: fish
+                .iter()
+                .map(|(fish, transform, direction)| SavedFish {
+                    t: fish.t,
+                    x: transform.translation.x,
+                    depth: viewport.ground_level - transform.translation.y,
+                    right: *direction == Direction::Right,
+                })
+                .collect(),
+            history: stats.history.clone(),
+            stats_day: stats.day,
+            milestones: progress.reached.iter().copied().collect(),
+            records: records.clone(),
+            contracts: contracts.clone(),
+        };
+        match save.write() {
+            Ok(()) => info!("Saved day {}", calendar.day()),
+            Err(error) => info!("Save failed: {}", error),
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        match SaveGame::read() {
+            Ok(save) => {
+                pending.save = Some(save);
+                next_state.set(GameState::Restart);
+            }
+            Err(error) => info!("Load failed: {}", error),
+        }
+    }
+}
+
+/// Clears the run before the level is set up again.
+pub fn restart(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<Layer>, With<Camera2d>, With<FishingHook>, With<Hud>)>>,
+    mut population: ResMut<PopulationTimer>,
+) {
+    info!("Restarting");
+    for entity in query {
+        commands.entity(entity).despawn();
+    }
+    commands.insert_resource(Calendar::default());
+    commands.insert_resource(FishStats::default());
+    commands.insert_resource(Ecosystem::default());
+    commands.insert_resource(Progress::default());
+    commands.insert_resource(Records::default());
+    commands.insert_resource(Contracts::default());
+    commands.insert_resource(DialogueMarks::default());
+    commands.insert_resource(GameOutcome::default());
+    population.timer.reset();
+}
+
+/// Applies the pending save on the new level, then resumes the game.
+pub fn apply_load(
+    mut commands: Commands,
+    mut pending: ResMut<PendingLoad>,
+    species: Res<FishSpecies>,
+    viewport: Res<ViewportConfig>,
+    players: Query<&mut Player>,
+    fauna: Query<Entity, With<FaunaLayer>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    next_state.set(GameState::InGame);
+    let Some(save) = pending.save.take() else {
+        return;
+    };
+
+    info!("Loading day {}", (save.secs / K_SECS_IN_DAY) as u32);
+    for mut player in players {
+        save.player.apply(&mut player);
+    }
+    if let Ok(fauna) = fauna.single() {
+        for fish in &save.fish {
+            if let Some(def) = species.get(fish.t) {
+                let id = spawn_fish(
+                    &mut commands,
+                    def,
+                    Vec2::new(fish.x, viewport.ground_level - fish.depth),
+                    fish.right,
+                    viewport.ground_level,
+                );
+                commands.entity(fauna).add_child(id);
+            }
+        }
+    }
+    commands.insert_resource(Calendar { secs: save.secs });
+    commands.insert_resource(FishStats {
+        history: save.history,
+        day: save.stats_day,
+        // The saved fish are the population, the zones only refill it.
+        seeded: true,
+        ..default()
+    });
+    commands.insert_resource(Progress {
+        reached: save.milestones.into_iter().collect(),
+        message: None,
+    });
+    commands.insert_resource(save.records);
+    commands.insert_resource(save.contracts);
+}